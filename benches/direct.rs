@@ -0,0 +1,89 @@
+// Direct summation over the structure of arrays, against the loop over bodies it replaced. Run
+// with `cargo bench --bench direct`.
+use glam::DVec3;
+use planet_sim::sim::gravity::GravityType;
+use std::{hint::black_box, time::Instant};
+
+// A body as stored before the structure of arrays, scratch state included, though only some of it
+// is read
+#[allow(dead_code)]
+struct Body {
+    id: usize,
+    position: DVec3,
+    velocity: DVec3,
+    mass: f64,
+    n_pos: DVec3,
+    n_vel: DVec3,
+}
+
+// The force loop from before the structure of arrays
+fn array_of_structs(bodies: &[Body], g: f64) -> Vec<DVec3> {
+    bodies
+        .iter()
+        .map(|body| {
+            let mut acceleration = DVec3::ZERO;
+            for other in bodies {
+                if body.id == other.id {
+                    continue;
+                }
+
+                let x = other.position - body.position;
+                acceleration += g * other.mass / x.length().powi(3) * x;
+            }
+            acceleration
+        })
+        .collect()
+}
+
+// Fastest of a few runs, in microseconds
+fn time(mut run: impl FnMut()) -> f64 {
+    (0..10)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed().as_secs_f64() * 1e6
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn main() {
+    let g = 4.0 * std::f64::consts::PI * std::f64::consts::PI;
+
+    for n in [1000, 4000] {
+        // Spread over a unit cube by a golden ratio sequence
+        let positions = (0..n)
+            .map(|i| {
+                let i = i as f64;
+                DVec3::new(
+                    i * 0.618_034 % 1.0,
+                    i * 0.754_878 % 1.0,
+                    i * 0.569_840 % 1.0,
+                )
+            })
+            .collect::<Vec<_>>();
+        let masses = vec![1.0 / n as f64; n];
+        let bodies = (0..n)
+            .map(|i| Body {
+                id: i,
+                position: positions[i],
+                velocity: DVec3::ZERO,
+                mass: masses[i],
+                n_pos: DVec3::ZERO,
+                n_vel: DVec3::ZERO,
+            })
+            .collect::<Vec<_>>();
+
+        let direct = GravityType::Direct.gravity();
+        let before = time(|| {
+            black_box(array_of_structs(black_box(&bodies), g));
+        });
+        let after = time(|| {
+            black_box(direct.accelerations(black_box(&positions), &masses, g, 0.0));
+        });
+
+        println!(
+            "N = {n}: {before:.0} µs before, {after:.0} µs after, {:.2}x faster",
+            before / after
+        );
+    }
+}
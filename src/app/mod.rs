@@ -1,12 +1,14 @@
 use self::controller::CameraController;
 use crate::engine::cam::{Camera3D, Projection};
 use std::time::Duration;
-use winit::event::{KeyboardInput, WindowEvent};
+use winit::event::{ElementState, KeyboardInput, VirtualKeyCode, WindowEvent};
 
 pub struct App {
     camera: Camera3D,
     projection: Projection,
     camera_controller: CameraController,
+    // Runs the sim backwards, which only retraces its path under a time-reversible integrator
+    reversed: bool,
 }
 
 impl App {
@@ -19,6 +21,7 @@ impl App {
             ),
             projection: Projection::new(width, height, (45.0_f32).to_radians(), 0.1, 100.0),
             camera_controller: CameraController::new(4.0, 0.4),
+            reversed: false,
         }
     }
 
@@ -28,6 +31,20 @@ impl App {
 
     pub fn input(&mut self, event: &WindowEvent) -> bool {
         match event {
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        virtual_keycode: Some(VirtualKeyCode::R),
+                        state,
+                        ..
+                    },
+                ..
+            } => {
+                if *state == ElementState::Pressed {
+                    self.reversed = !self.reversed;
+                }
+                true
+            }
             WindowEvent::KeyboardInput {
                 input:
                     KeyboardInput {
@@ -49,6 +66,15 @@ impl App {
         self.camera_controller.update_camera(&mut self.camera, dt);
     }
 
+    // Sign of the sim's time step
+    pub fn time_direction(&self) -> f64 {
+        if self.reversed {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
     pub fn uniform_data(&self) -> Vec<u8> {
         bytemuck::cast_slice(&[self.projection.mat() * self.camera.mat()]).to_vec()
     }
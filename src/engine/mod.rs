@@ -1,14 +1,12 @@
 use crate::app::App;
 
 use self::{
-    mesh::*,
-    object::{EngineKey, EngineObject, Instance},
+    object::Instance,
     pipeline::PipelineBuilder,
     shader::Shader,
     uniform::{CameraBuffer, UniformBuffer},
 };
 use anyhow::{Context, Result};
-use glam::{Quat, Vec2, Vec3};
 use std::{time::Instant, vec};
 use winit::{
     event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
@@ -139,29 +137,25 @@ impl Engine {
                 Event::WindowEvent {
                     ref event,
                     window_id,
-                } if window_id == self.window.window().id() => {
-                    if !app.input(event) {
-                        match event {
-                            WindowEvent::CloseRequested
-                            | WindowEvent::KeyboardInput {
-                                input:
-                                    KeyboardInput {
-                                        state: ElementState::Pressed,
-                                        virtual_keycode: Some(VirtualKeyCode::Escape),
-                                        ..
-                                    },
+                } if window_id == self.window.window().id() && !app.input(event) => match event {
+                    WindowEvent::CloseRequested
+                    | WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::Escape),
                                 ..
-                            } => *control_flow = ControlFlow::Exit,
-                            WindowEvent::Resized(size) => {
-                                if let Some(config) = self.window.resize(renderer.config(), *size) {
-                                    app.resize(config.width, config.height);
-                                    renderer.configure(config);
-                                }
-                            }
-                            _ => {}
+                            },
+                        ..
+                    } => *control_flow = ControlFlow::Exit,
+                    WindowEvent::Resized(size) => {
+                        if let Some(config) = self.window.resize(renderer.config(), *size) {
+                            app.resize(config.width, config.height);
+                            renderer.configure(config);
                         }
                     }
-                }
+                    _ => {}
+                },
                 Event::RedrawRequested(window_id) if window_id == self.window.window().id() => {
                     let now = Instant::now();
                     let dt = now - last_render;
@@ -171,7 +165,14 @@ impl Engine {
 
                     renderer.request_buffer_update(camera_binding.id(), &app.uniform_data());
 
-                    let (k, o) = scene.step_sim(dt.as_secs_f64() / 12.0);
+                    // Reversing any other integrator wanders off somewhere new
+                    let direction = if scene.time_reversible() {
+                        app.time_direction()
+                    } else {
+                        1.0
+                    };
+                    let (k, o) =
+                        scene.step_sim(direction * dt.as_secs_f64() / 12.0, renderer.device());
                     renderer.instance_buffer_update(
                         k,
                         bytemuck::cast_slice(
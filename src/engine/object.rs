@@ -125,18 +125,19 @@ impl EngineObject {
         }
     }
 
-    pub fn staged_instance_buffer<'a>(&'a self) -> RawStageBuffer<'a> {
+    // `None` when there are no instances to write
+    pub fn staged_instance_buffer<'a>(&'a self) -> Option<RawStageBuffer<'a>> {
         let inst_raw_size = std::mem::size_of::<InstanceRaw>() as u64;
         let ct_inst = self.instances.len() as u64;
 
-        RawStageBuffer {
+        Some(RawStageBuffer {
             buffer: &self.instance_buffer,
             offset: 0x0,
-            size: wgpu::BufferSize::new(inst_raw_size * ct_inst).unwrap(),
-        }
+            size: wgpu::BufferSize::new(inst_raw_size * ct_inst)?,
+        })
     }
 
-    pub fn odc(&self) -> Box<dyn renderer::Odc + '_> {
+    pub fn odc(&self) -> Box<dyn renderer::Odc<'_> + '_> {
         let instance_buffer = self.instance_buffer.slice(..);
         let vertex_buffer = self.mesh.vertex_buffer().slice(..);
 
@@ -146,7 +147,6 @@ impl EngineObject {
                 instance_buffer,
                 i.slice(..),
                 self.mesh.indices(),
-                // TODO: Better storage of instance buffer len w/ recreation on add/remove
                 0..self.instances.len() as u32,
                 None,
                 None,
@@ -171,4 +171,15 @@ impl EngineObject {
     pub fn instances_mut(&mut self) -> &mut [Instance] {
         &mut self.instances
     }
+
+    // Replaces every instance, recreating the instance buffer only when it's too small to hold
+    // them. A larger buffer is left as is, since only the first `instances.len()` are drawn.
+    pub fn set_instances(&mut self, instances: Vec<Instance>, device: &wgpu::Device) {
+        let inst_raw_size = std::mem::size_of::<InstanceRaw>() as u64;
+        if inst_raw_size * instances.len() as u64 > self.instance_buffer.size() {
+            self.instance_buffer = Instance::buffer(&instances, device);
+        }
+
+        self.instances = instances;
+    }
 }
@@ -115,7 +115,9 @@ impl Renderer {
         }
 
         for (key, value) in self.instance_data.iter() {
-            let staged = scene.objects()[key].staged_instance_buffer();
+            let Some(staged) = scene.objects()[key].staged_instance_buffer() else {
+                continue;
+            };
             let mut buffer = self.staging_belt.write_buffer(
                 &mut encoder,
                 staged.buffer(),
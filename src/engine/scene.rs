@@ -1,18 +1,16 @@
-use std::borrow::Cow;
-
 use super::cam::Camera2D;
 use super::mesh::{Mesh, Quad};
-use super::object::{EngineKey, EngineObject, Instance, InstanceRaw};
+use super::object::{EngineKey, EngineObject, Instance};
 use super::renderer;
-use crate::sim::Sim;
+use crate::sim::{integrator::IntegratorType, Sim};
 use glam::{Vec2, Vec3};
-use slotmap::HopSlotMap;
+use slotmap::DenseSlotMap;
 
 pub struct Scene {
     // https://docs.rs/slotmap/latest/slotmap/#choosing-slotmap-hopslotmap-or-denseslotmap
-    // `HopSlotMap` has a ~2x as slow insertion and deletion times compared to `SlotMap`
-    // Iteration is significantly faster, however
-    engine_objects: HopSlotMap<EngineKey, EngineObject>,
+    // `DenseSlotMap` has slower insertion and deletion times compared to `SlotMap`
+    // Iteration is as fast as a `Vec`, however
+    engine_objects: DenseSlotMap<EngineKey, EngineObject>,
     camera: Camera2D,
     sim: Sim,
     sim_key: EngineKey,
@@ -21,12 +19,13 @@ pub struct Scene {
 
 impl Scene {
     pub fn new(renderer: &renderer::Renderer) -> Self {
-        let mut sm: HopSlotMap<EngineKey, EngineObject> = HopSlotMap::with_capacity_and_key(1024);
-        let sim = Sim::new();
+        let mut sm: DenseSlotMap<EngineKey, EngineObject> =
+            DenseSlotMap::with_capacity_and_key(1024);
+        // Time-reversible, so running it backwards retraces the orbits
+        let sim = Sim::new(IntegratorType::Yoshida);
         let instances = sim
             .system()
             .bodies()
-            .iter()
             .map(|b| {
                 Instance::new(
                     b.position().as_vec3(),
@@ -70,23 +69,44 @@ impl Scene {
         )
     }
 
-    pub fn step_sim(&mut self, dt: f64) -> (EngineKey, &EngineObject) {
-        self.sim.step(dt);
+    // Whether the sim can run backwards along the path it came
+    pub fn time_reversible(&self) -> bool {
+        self.sim.system().is_time_reversible()
+    }
 
-        let object = &mut self.engine_objects[self.sim_key];
-        for (i, b) in object
-            .instances_mut()
-            .iter_mut()
-            .zip(self.sim.system().bodies())
-        {
-            let color = self
-                .grad
-                .eval_continuous(b.velocity().length_squared() / 100.0);
-
-            i.position = b.position().as_vec3();
-            i.color = Self::rgb_vec(color.r, color.g, color.b);
+    // Bodies can merge or escape during a step, so the instances are rebuilt from scratch
+    pub fn step_sim(&mut self, dt: f64, device: &wgpu::Device) -> (EngineKey, &EngineObject) {
+        // A halted sim has already said why
+        if self.sim.system().halted().is_none() {
+            if let Err(error) = self.sim.step(dt) {
+                log::warn!("{error}");
+            }
+        }
+
+        for event in self.sim.drain_events() {
+            log::info!("{event:?}");
         }
 
+        let instances = self
+            .sim
+            .system()
+            .bodies()
+            .map(|b| {
+                let color = self
+                    .grad
+                    .eval_continuous(b.velocity().length_squared() / 100.0);
+
+                Instance::new(
+                    b.position().as_vec3(),
+                    glam::Quat::from_rotation_z(0.0),
+                    Self::rgb_vec(color.r, color.g, color.b),
+                )
+            })
+            .collect::<Vec<Instance>>();
+
+        let object = &mut self.engine_objects[self.sim_key];
+        object.set_instances(instances, device);
+
         (self.sim_key, object)
     }
 
@@ -94,11 +114,11 @@ impl Scene {
         self.engine_objects.insert(object)
     }
 
-    pub fn objects(&self) -> &HopSlotMap<EngineKey, EngineObject> {
+    pub fn objects(&self) -> &DenseSlotMap<EngineKey, EngineObject> {
         &self.engine_objects
     }
 
-    pub fn objects_mut(&mut self) -> &mut HopSlotMap<EngineKey, EngineObject> {
+    pub fn objects_mut(&mut self) -> &mut DenseSlotMap<EngineKey, EngineObject> {
         &mut self.engine_objects
     }
 
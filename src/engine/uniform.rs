@@ -53,10 +53,16 @@ impl ScreenBuffer {
     }
 }
 
+impl Default for ScreenBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UniformBuffer for ScreenBuffer {
     fn bind(&self, data: &[u8], device: &wgpu::Device) -> UniformBufferBinding {
         let group_layout = self.create_bind_group_layout(device);
-        let buffer = self.create_buffer(device, &data);
+        let buffer = self.create_buffer(device, data);
         let group = self.create_bind_group(device, &buffer, &group_layout);
 
         UniformBufferBinding {
@@ -119,10 +125,16 @@ impl CameraBuffer {
     }
 }
 
+impl Default for CameraBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UniformBuffer for CameraBuffer {
     fn bind(&self, data: &[u8], device: &wgpu::Device) -> UniformBufferBinding {
         let group_layout = self.create_bind_group_layout(device);
-        let buffer = self.create_buffer(device, &data);
+        let buffer = self.create_buffer(device, data);
         let group = self.create_bind_group(device, &buffer, &group_layout);
 
         UniformBufferBinding {
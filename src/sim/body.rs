@@ -1,28 +1,44 @@
+use super::{
+    orbit::{Elements, Orbit},
+    units::Units,
+};
 use glam::f64::DVec3;
-use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 static BODY_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
+// Id the next body built will get
+pub(super) fn next_id() -> usize {
+    BODY_COUNTER.load(Ordering::SeqCst)
+}
+
+// Keeps bodies built from here on from reusing any id below `next`
+pub(super) fn reserve_ids(next: usize) {
+    BODY_COUNTER.fetch_max(next, Ordering::SeqCst);
+}
+
+// A body on its own, before insertion into a system or as a snapshot of one of its entries. The
+// system itself keeps each property in its own array.
+#[derive(Clone, Copy, Debug)]
 pub struct Body {
     id: usize,
 
     position: DVec3,
     velocity: DVec3,
     mass: f64,
-
-    n_pos: DVec3,
-    n_vel: DVec3,
+    // Zero for point masses, which never collide
+    radius: f64,
 }
 
 impl Body {
-    pub fn apply(&mut self, position: DVec3, velocity: DVec3) {
-        self.n_pos = position;
-        self.n_vel = velocity;
-    }
-
-    pub fn advance(&mut self) {
-        self.position = self.n_pos;
-        self.velocity = self.n_vel;
+    pub(super) fn new(id: usize, position: DVec3, velocity: DVec3, mass: f64, radius: f64) -> Self {
+        Self {
+            id,
+            position,
+            velocity,
+            mass,
+            radius,
+        }
     }
 
     pub fn position(&self) -> DVec3 {
@@ -37,15 +53,34 @@ impl Body {
         self.mass
     }
 
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    // Feels the gravity of the massive bodies, without pulling on anything
+    pub fn is_test_particle(&self) -> bool {
+        self.mass == 0.0
+    }
+
     pub fn id(&self) -> usize {
         self.id
     }
+
+    // Osculating elements of the body's orbit about `primary`, in the units of the sim
+    pub fn elements(&self, primary: &Body, units: Units) -> Elements {
+        Elements::new(
+            self.position - primary.position,
+            self.velocity - primary.velocity,
+            units.gravitational_constant() * (self.mass + primary.mass),
+        )
+    }
 }
 
 pub struct BodyBuilder {
     mass: f64,
     position: Option<DVec3>,
     velocity: Option<DVec3>,
+    radius: Option<f64>,
 }
 
 impl BodyBuilder {
@@ -54,9 +89,16 @@ impl BodyBuilder {
             mass,
             position: None,
             velocity: None,
+            radius: None,
         }
     }
 
+    // Massless, so it only feels the gravity of massive bodies. Forces between massive bodies
+    // and on test particles then cost O(massive × total) together, rather than O(total²).
+    pub fn test_particle() -> Self {
+        Self::new(0.0)
+    }
+
     pub fn with_position(mut self, position: DVec3) -> Self {
         self.position = Some(position);
         self
@@ -67,17 +109,29 @@ impl BodyBuilder {
         self
     }
 
-    pub fn build(&self) -> Body {
-        let position = self.position.unwrap_or(DVec3::ZERO);
-        let velocity = self.velocity.unwrap_or(DVec3::ZERO);
+    // Places the body on `orbit` about `primary`, in the units of the sim, replacing any position
+    // or velocity given
+    pub fn with_orbit(mut self, primary: &Body, orbit: Orbit, units: Units) -> Self {
+        let mu = units.gravitational_constant() * (primary.mass() + self.mass);
+        let (position, velocity) = orbit.state(mu);
 
-        Body {
-            id: BODY_COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst),
-            position,
-            velocity,
-            mass: self.mass,
-            n_pos: position,
-            n_vel: velocity,
-        }
+        self.position = Some(primary.position() + position);
+        self.velocity = Some(primary.velocity() + velocity);
+        self
+    }
+
+    pub fn with_radius(mut self, radius: f64) -> Self {
+        self.radius = Some(radius);
+        self
+    }
+
+    pub fn build(&self) -> Body {
+        Body::new(
+            BODY_COUNTER.fetch_add(1, Ordering::SeqCst),
+            self.position.unwrap_or(DVec3::ZERO),
+            self.velocity.unwrap_or(DVec3::ZERO),
+            self.mass,
+            self.radius.unwrap_or(0.0),
+        )
     }
 }
@@ -0,0 +1,514 @@
+use super::{
+    collision::{Collision, CollisionPolicy},
+    cr3bp::Cr3bp,
+    diagnostics::Diagnostics,
+    encounter::EncounterRadius,
+    escape::EscapeCriterion,
+    frame::Frame,
+    gravity::{fmm, GravityType},
+    integrator::IntegratorType,
+    system::Settings,
+    units::Units,
+};
+use glam::f64::DVec3;
+use std::{fmt, io};
+
+// Starts every checkpoint, followed by the version of its format
+const MAGIC: &[u8; 4] = b"PSIM";
+pub const VERSION: u32 = 1;
+
+// Encoded sizes, for `Decoder::count`
+pub const USIZE: usize = 8;
+pub const F64: usize = 8;
+pub const VEC3: usize = 3 * F64;
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    NotACheckpoint,
+    // Written by a different version of the format
+    Version(u32),
+    Truncated,
+    Invalid(&'static str),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io(error) => write!(f, "couldn't access checkpoint: {error}"),
+            CheckpointError::NotACheckpoint => write!(f, "not a checkpoint"),
+            CheckpointError::Version(version) => write!(
+                f,
+                "checkpoint is version {version}, only version {VERSION} is supported"
+            ),
+            CheckpointError::Truncated => write!(f, "checkpoint is truncated"),
+            CheckpointError::Invalid(what) => write!(f, "checkpoint has an invalid {what}"),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<io::Error> for CheckpointError {
+    fn from(error: io::Error) -> Self {
+        CheckpointError::Io(error)
+    }
+}
+
+// Writes a checkpoint, little-endian, with floats kept bit for bit
+pub struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    pub(super) fn new() -> Self {
+        let mut encoder = Self {
+            bytes: MAGIC.to_vec(),
+        };
+        encoder.u32(VERSION);
+        encoder
+    }
+
+    pub(super) fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn usize(&mut self, value: usize) {
+        self.u64(value as u64);
+    }
+
+    pub fn f64(&mut self, value: f64) {
+        self.u64(value.to_bits());
+    }
+
+    pub fn vec3(&mut self, value: DVec3) {
+        self.f64(value.x);
+        self.f64(value.y);
+        self.f64(value.z);
+    }
+
+    pub fn option_f64(&mut self, value: Option<f64>) {
+        match value {
+            Some(value) => {
+                self.u8(1);
+                self.f64(value);
+            }
+            None => self.u8(0),
+        }
+    }
+
+    pub fn option_usize(&mut self, value: Option<usize>) {
+        match value {
+            Some(value) => {
+                self.u8(1);
+                self.usize(value);
+            }
+            None => self.u8(0),
+        }
+    }
+}
+
+// Reads back what an `Encoder` wrote, in the same order
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub(super) fn new(bytes: &'a [u8]) -> Result<Self, CheckpointError> {
+        let bytes = bytes
+            .strip_prefix(MAGIC)
+            .ok_or(CheckpointError::NotACheckpoint)?;
+
+        let mut decoder = Self { bytes };
+        let version = decoder.u32()?;
+        if version != VERSION {
+            return Err(CheckpointError::Version(version));
+        }
+
+        Ok(decoder)
+    }
+
+    // Fails if anything is left over
+    pub(super) fn finish(self) -> Result<(), CheckpointError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(CheckpointError::Invalid("length"))
+        }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], CheckpointError> {
+        if self.bytes.len() < N {
+            return Err(CheckpointError::Truncated);
+        }
+
+        let (taken, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(taken.try_into().unwrap())
+    }
+
+    pub fn u8(&mut self) -> Result<u8, CheckpointError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32, CheckpointError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, CheckpointError> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    pub fn usize(&mut self) -> Result<usize, CheckpointError> {
+        usize::try_from(self.u64()?).map_err(|_| CheckpointError::Invalid("count"))
+    }
+
+    pub fn f64(&mut self) -> Result<f64, CheckpointError> {
+        Ok(f64::from_bits(self.u64()?))
+    }
+
+    pub fn vec3(&mut self) -> Result<DVec3, CheckpointError> {
+        Ok(DVec3::new(self.f64()?, self.f64()?, self.f64()?))
+    }
+
+    pub fn option_f64(&mut self) -> Result<Option<f64>, CheckpointError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.f64()?)),
+            _ => Err(CheckpointError::Invalid("option")),
+        }
+    }
+
+    pub fn option_usize(&mut self) -> Result<Option<usize>, CheckpointError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.usize()?)),
+            _ => Err(CheckpointError::Invalid("option")),
+        }
+    }
+
+    // Count of items still to come, each at least `size` bytes, so a corrupt count can't make
+    // the reader allocate more than the checkpoint could hold
+    pub fn count(&mut self, size: usize) -> Result<usize, CheckpointError> {
+        let len = self.usize()?;
+        if len.saturating_mul(size) > self.bytes.len() {
+            return Err(CheckpointError::Truncated);
+        }
+
+        Ok(len)
+    }
+}
+
+// Settings and other plain values a checkpoint holds, each written as a tag for its variant
+// followed by its fields
+pub(super) trait Checkpoint: Sized {
+    fn save(&self, encoder: &mut Encoder);
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError>;
+}
+
+impl<T: Checkpoint> Checkpoint for Option<T> {
+    fn save(&self, encoder: &mut Encoder) {
+        match self {
+            Some(value) => {
+                encoder.u8(1);
+                value.save(encoder);
+            }
+            None => encoder.u8(0),
+        }
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        match decoder.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::load(decoder)?)),
+            _ => Err(CheckpointError::Invalid("option")),
+        }
+    }
+}
+
+impl Checkpoint for IntegratorType {
+    fn save(&self, encoder: &mut Encoder) {
+        match *self {
+            IntegratorType::Euler => encoder.u8(0),
+            IntegratorType::SemiImplicitEuler => encoder.u8(1),
+            IntegratorType::Leapfrog => encoder.u8(2),
+            IntegratorType::Rk4 => encoder.u8(3),
+            IntegratorType::Yoshida => encoder.u8(4),
+            IntegratorType::DormandPrince { atol, rtol } => {
+                encoder.u8(5);
+                encoder.f64(atol);
+                encoder.f64(rtol);
+            }
+            IntegratorType::Ias15 { epsilon } => {
+                encoder.u8(6);
+                encoder.f64(epsilon);
+            }
+            IntegratorType::WisdomHolman => encoder.u8(7),
+        }
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(match decoder.u8()? {
+            0 => IntegratorType::Euler,
+            1 => IntegratorType::SemiImplicitEuler,
+            2 => IntegratorType::Leapfrog,
+            3 => IntegratorType::Rk4,
+            4 => IntegratorType::Yoshida,
+            5 => IntegratorType::DormandPrince {
+                atol: decoder.f64()?,
+                rtol: decoder.f64()?,
+            },
+            6 => IntegratorType::Ias15 {
+                epsilon: decoder.f64()?,
+            },
+            7 => IntegratorType::WisdomHolman,
+            _ => return Err(CheckpointError::Invalid("integrator")),
+        })
+    }
+}
+
+impl Checkpoint for GravityType {
+    fn save(&self, encoder: &mut Encoder) {
+        match *self {
+            GravityType::Direct => encoder.u8(0),
+            GravityType::BarnesHut { theta } => {
+                encoder.u8(1);
+                encoder.f64(theta);
+            }
+            GravityType::Fmm { order } => {
+                encoder.u8(2);
+                encoder.usize(order);
+            }
+        }
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(match decoder.u8()? {
+            0 => GravityType::Direct,
+            1 => GravityType::BarnesHut {
+                theta: decoder.f64()?,
+            },
+            2 => match decoder.usize()? {
+                order @ 1..=fmm::MAX_ORDER => GravityType::Fmm { order },
+                _ => return Err(CheckpointError::Invalid("gravity backend")),
+            },
+            _ => return Err(CheckpointError::Invalid("gravity backend")),
+        })
+    }
+}
+
+impl Checkpoint for Units {
+    fn save(&self, encoder: &mut Encoder) {
+        match *self {
+            Units::Astronomical => encoder.u8(0),
+            Units::Si => encoder.u8(1),
+            Units::Kilometers => encoder.u8(2),
+            Units::NBody { length, mass } => {
+                encoder.u8(3);
+                encoder.f64(length);
+                encoder.f64(mass);
+            }
+        }
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(match decoder.u8()? {
+            0 => Units::Astronomical,
+            1 => Units::Si,
+            2 => Units::Kilometers,
+            3 => Units::NBody {
+                length: decoder.f64()?,
+                mass: decoder.f64()?,
+            },
+            _ => return Err(CheckpointError::Invalid("units")),
+        })
+    }
+}
+
+impl Checkpoint for CollisionPolicy {
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.u8(match self {
+            CollisionPolicy::Merge => 0,
+            CollisionPolicy::Bounce => 1,
+            CollisionPolicy::Halt => 2,
+        });
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(match decoder.u8()? {
+            0 => CollisionPolicy::Merge,
+            1 => CollisionPolicy::Bounce,
+            2 => CollisionPolicy::Halt,
+            _ => return Err(CheckpointError::Invalid("collision policy")),
+        })
+    }
+}
+
+impl Checkpoint for EncounterRadius {
+    fn save(&self, encoder: &mut Encoder) {
+        let (tag, value) = match *self {
+            EncounterRadius::Distance(distance) => (0, distance),
+            EncounterRadius::Hill(n) => (1, n),
+        };
+        encoder.u8(tag);
+        encoder.f64(value);
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(match decoder.u8()? {
+            0 => EncounterRadius::Distance(decoder.f64()?),
+            1 => EncounterRadius::Hill(decoder.f64()?),
+            _ => return Err(CheckpointError::Invalid("encounter radius")),
+        })
+    }
+}
+
+impl Checkpoint for EscapeCriterion {
+    fn save(&self, encoder: &mut Encoder) {
+        match *self {
+            EscapeCriterion::Distance(cutoff) => {
+                encoder.u8(0);
+                encoder.f64(cutoff);
+            }
+            EscapeCriterion::Unbound => encoder.u8(1),
+            EscapeCriterion::UnboundBeyond(cutoff) => {
+                encoder.u8(2);
+                encoder.f64(cutoff);
+            }
+        }
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(match decoder.u8()? {
+            0 => EscapeCriterion::Distance(decoder.f64()?),
+            1 => EscapeCriterion::Unbound,
+            2 => EscapeCriterion::UnboundBeyond(decoder.f64()?),
+            _ => return Err(CheckpointError::Invalid("escape criterion")),
+        })
+    }
+}
+
+impl Checkpoint for Frame {
+    fn save(&self, encoder: &mut Encoder) {
+        match *self {
+            Frame::AsGiven => encoder.u8(0),
+            Frame::Barycentric => encoder.u8(1),
+            Frame::Heliocentric => encoder.u8(2),
+            Frame::BodyCentric(id) => {
+                encoder.u8(3);
+                encoder.usize(id);
+            }
+        }
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(match decoder.u8()? {
+            0 => Frame::AsGiven,
+            1 => Frame::Barycentric,
+            2 => Frame::Heliocentric,
+            3 => Frame::BodyCentric(decoder.usize()?),
+            _ => return Err(CheckpointError::Invalid("frame")),
+        })
+    }
+}
+
+impl Checkpoint for Cr3bp {
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.f64(self.mu());
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        let mu = decoder.f64()?;
+        if !(mu > 0.0 && mu <= 0.5) {
+            return Err(CheckpointError::Invalid("restricted problem"));
+        }
+
+        Ok(Cr3bp::new(mu))
+    }
+}
+
+impl Checkpoint for Diagnostics {
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.f64(self.time);
+        encoder.f64(self.kinetic_energy);
+        encoder.f64(self.potential_energy);
+        encoder.vec3(self.angular_momentum);
+        encoder.vec3(self.momentum);
+        encoder.vec3(self.center_of_mass);
+        encoder.f64(self.mass);
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(Diagnostics {
+            time: decoder.f64()?,
+            kinetic_energy: decoder.f64()?,
+            potential_energy: decoder.f64()?,
+            angular_momentum: decoder.vec3()?,
+            momentum: decoder.vec3()?,
+            center_of_mass: decoder.vec3()?,
+            mass: decoder.f64()?,
+        })
+    }
+}
+
+impl Checkpoint for Collision {
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.f64(self.time);
+        encoder.usize(self.bodies[0]);
+        encoder.usize(self.bodies[1]);
+        encoder.vec3(self.position);
+        encoder.f64(self.impact_speed);
+        encoder.option_usize(self.merged);
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(Collision {
+            time: decoder.f64()?,
+            bodies: [decoder.usize()?, decoder.usize()?],
+            position: decoder.vec3()?,
+            impact_speed: decoder.f64()?,
+            merged: decoder.option_usize()?,
+        })
+    }
+}
+
+impl Checkpoint for Settings {
+    fn save(&self, encoder: &mut Encoder) {
+        self.units.save(encoder);
+        encoder.f64(self.softening);
+        self.collisions.save(encoder);
+        self.encounters.save(encoder);
+        self.escapes.save(encoder);
+        encoder.u8(self.relativity as u8);
+        self.restricted.save(encoder);
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(Settings {
+            units: Units::load(decoder)?,
+            softening: decoder.f64()?,
+            collisions: CollisionPolicy::load(decoder)?,
+            encounters: Option::load(decoder)?,
+            escapes: Option::load(decoder)?,
+            relativity: match decoder.u8()? {
+                0 => false,
+                1 => true,
+                _ => return Err(CheckpointError::Invalid("relativity")),
+            },
+            restricted: Option::load(decoder)?,
+        })
+    }
+}
@@ -0,0 +1,23 @@
+use glam::f64::DVec3;
+
+// What happens when two bodies with a radius touch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollisionPolicy {
+    // Combine into a single body, conserving mass, momentum and volume
+    Merge,
+    // Bounce elastically off each other
+    Bounce,
+    // Stop with an error, refusing to step again until either body is removed
+    Halt,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Collision {
+    pub time: f64,
+    pub bodies: [usize; 2],
+    // Center of mass of the pair
+    pub position: DVec3,
+    pub impact_speed: f64,
+    // Body the pair merged into, if it did
+    pub merged: Option<usize>,
+}
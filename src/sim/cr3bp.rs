@@ -0,0 +1,126 @@
+use glam::f64::DVec3;
+
+// Circular restricted three-body problem, in the frame co-rotating with two primaries on circular
+// orbits about their barycenter. Units are normalized so that G, the total mass of the primaries,
+// their separation and their angular velocity are all 1. The primaries sit at (-μ, 0, 0) and
+// (1 - μ, 0, 0), rotating about z.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cr3bp {
+    // m₂ / (m₁ + m₂), of the lighter primary, in (0, 0.5]
+    mu: f64,
+}
+
+impl Cr3bp {
+    pub fn new(mu: f64) -> Self {
+        Self { mu }
+    }
+
+    pub fn mu(&self) -> f64 {
+        self.mu
+    }
+
+    pub fn primaries(&self) -> [DVec3; 2] {
+        [
+            DVec3::new(-self.mu, 0.0, 0.0),
+            DVec3::new(1.0 - self.mu, 0.0, 0.0),
+        ]
+    }
+
+    // Ω = (x² + y²)/2 + (1 - μ)/r₁ + μ/r₂, including the centrifugal term
+    pub fn pseudo_potential(&self, position: DVec3) -> f64 {
+        let [r1, r2] = self.primaries().map(|p| (position - p).length());
+        (position.x * position.x + position.y * position.y) / 2.0
+            + (1.0 - self.mu) / r1
+            + self.mu / r2
+    }
+
+    // C = 2Ω - v², conserved along every trajectory
+    pub fn jacobi_constant(&self, position: DVec3, velocity: DVec3) -> f64 {
+        2.0 * self.pseudo_potential(position) - velocity.length_squared()
+    }
+
+    // 2Ω - C, the squared speed a particle with Jacobi constant `jacobi` would have at `position`.
+    // Negative where it can't reach, so its zero level set is the zero-velocity surface.
+    pub fn zero_velocity(&self, position: DVec3, jacobi: f64) -> f64 {
+        2.0 * self.pseudo_potential(position) - jacobi
+    }
+
+    // `zero_velocity` sampled over a `resolution` × `resolution` grid in the plane at height `z`,
+    // spanning [-extent, extent] in x and y, in rows of increasing y, for contouring
+    pub fn zero_velocity_grid(
+        &self,
+        jacobi: f64,
+        z: f64,
+        extent: f64,
+        resolution: usize,
+    ) -> Vec<f64> {
+        let spacing = 2.0 * extent / (resolution.max(2) - 1) as f64;
+        let coordinate = |k: usize| -extent + k as f64 * spacing;
+
+        (0..resolution)
+            .flat_map(|j| (0..resolution).map(move |i| DVec3::new(coordinate(i), coordinate(j), z)))
+            .map(|position| self.zero_velocity(position, jacobi))
+            .collect()
+    }
+
+    // Acceleration in the rotating frame, with the Coriolis and centrifugal terms
+    pub fn acceleration(&self, position: DVec3, velocity: DVec3) -> DVec3 {
+        let [p1, p2] = self.primaries();
+        let (x1, x2) = (position - p1, position - p2);
+        let gravity =
+            -(1.0 - self.mu) / x1.length().powi(3) * x1 - self.mu / x2.length().powi(3) * x2;
+
+        gravity
+            + DVec3::new(
+                2.0 * velocity.y + position.x,
+                -2.0 * velocity.x + position.y,
+                0.0,
+            )
+    }
+
+    // L1 to L5. L1 lies between the primaries, L2 beyond the lighter one and L3 beyond the heavier
+    // one, with L4 leading the lighter primary by 60° and L5 trailing it.
+    pub fn lagrange_points(&self) -> [DVec3; 5] {
+        let mu = self.mu;
+        let hill = (mu / 3.0).cbrt();
+
+        let collinear = |guess: f64| {
+            let mut x = guess;
+            for _ in 0..64 {
+                let (x1, x2) = (x + mu, x - 1.0 + mu);
+                let (r1, r2) = (x1.abs().powi(3), x2.abs().powi(3));
+
+                let f = x - (1.0 - mu) * x1 / r1 - mu * x2 / r2;
+                let df = 1.0 + 2.0 * (1.0 - mu) / r1 + 2.0 * mu / r2;
+                let delta = f / df;
+
+                x -= delta;
+                if delta.abs() <= f64::EPSILON * x.abs().max(1.0) {
+                    break;
+                }
+            }
+            DVec3::new(x, 0.0, 0.0)
+        };
+
+        let triangular = 3f64.sqrt() / 2.0;
+        [
+            collinear(1.0 - mu - hill),
+            collinear(1.0 - mu + hill),
+            collinear(-1.0 - 5.0 * mu / 12.0),
+            DVec3::new(0.5 - mu, triangular, 0.0),
+            DVec3::new(0.5 - mu, -triangular, 0.0),
+        ]
+    }
+
+    // Position and velocity in the inertial frame centered on the barycenter, at `time`, which
+    // coincides with the rotating frame at time 0
+    pub fn to_inertial(&self, position: DVec3, velocity: DVec3, time: f64) -> (DVec3, DVec3) {
+        let (sin, cos) = time.sin_cos();
+        let rotate = |x: DVec3| DVec3::new(cos * x.x - sin * x.y, sin * x.x + cos * x.y, x.z);
+
+        (
+            rotate(position),
+            rotate(velocity + DVec3::Z.cross(position)),
+        )
+    }
+}
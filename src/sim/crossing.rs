@@ -0,0 +1,68 @@
+use super::{body::Body, force::Bodies};
+
+// Which sign changes of an event function count
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    // From negative to positive, like the radial velocity at periapsis or z at an ascending node
+    Rising,
+    Falling,
+    Either,
+}
+
+// A scalar function of the state, whose zero crossings are located to within `tolerance` in time
+// and logged as events. Crossings are found from the sign at the end of each step, so a step
+// crossing zero twice shows neither.
+pub struct EventFunction {
+    name: &'static str,
+    direction: Direction,
+    tolerance: f64,
+    function: Box<dyn Fn(&Bodies) -> f64>,
+}
+
+impl EventFunction {
+    pub fn new(
+        name: &'static str,
+        direction: Direction,
+        tolerance: f64,
+        function: impl Fn(&Bodies) -> f64 + 'static,
+    ) -> Self {
+        Self {
+            name,
+            direction,
+            tolerance,
+            function: Box::new(function),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub(super) fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    pub(super) fn value(&self, bodies: &Bodies) -> f64 {
+        (self.function)(bodies)
+    }
+
+    pub(super) fn crosses(&self, before: f64, after: f64) -> bool {
+        let rising = before < 0.0 && after >= 0.0;
+        let falling = before > 0.0 && after <= 0.0;
+
+        match self.direction {
+            Direction::Rising => rising,
+            Direction::Falling => falling,
+            Direction::Either => rising || falling,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Crossing {
+    // Of the event function
+    pub name: &'static str,
+    pub time: f64,
+    // Every body at the crossing
+    pub bodies: Vec<Body>,
+}
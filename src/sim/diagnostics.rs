@@ -0,0 +1,78 @@
+use glam::f64::DVec3;
+
+// Conserved quantities of a system at one point in time
+#[derive(Clone, Copy, Debug)]
+pub struct Diagnostics {
+    pub time: f64,
+    pub kinetic_energy: f64,
+    // Including softening, matching the forces the bodies feel
+    pub potential_energy: f64,
+    pub angular_momentum: DVec3,
+    pub momentum: DVec3,
+    pub center_of_mass: DVec3,
+    pub mass: f64,
+}
+
+impl Diagnostics {
+    pub fn energy(&self) -> f64 {
+        self.kinetic_energy + self.potential_energy
+    }
+
+    // Where the center of mass should be at `time`, moving at a constant velocity
+    fn center_of_mass_at(&self, time: f64) -> DVec3 {
+        if self.mass > 0.0 {
+            self.center_of_mass + self.momentum / self.mass * (time - self.time)
+        } else {
+            self.center_of_mass
+        }
+    }
+}
+
+// Relative change in each conserved quantity between two sets of diagnostics. Momentum and the
+// center of mass are often zero to begin with, so they're relative to the sum of the magnitudes
+// of every body's momentum, and their mean distance from the center of mass, instead.
+#[derive(Clone, Copy, Debug)]
+pub struct Drift {
+    pub energy: f64,
+    pub angular_momentum: f64,
+    pub momentum: f64,
+    // Away from uniform motion at the initial momentum
+    pub center_of_mass: f64,
+}
+
+impl Drift {
+    pub(super) fn new(
+        initial: &Diagnostics,
+        current: &Diagnostics,
+        momentum_scale: f64,
+        length_scale: f64,
+    ) -> Self {
+        Self {
+            energy: relative(
+                (current.energy() - initial.energy()).abs(),
+                initial.energy().abs(),
+            ),
+            angular_momentum: relative(
+                (current.angular_momentum - initial.angular_momentum).length(),
+                initial.angular_momentum.length(),
+            ),
+            momentum: relative(
+                (current.momentum - initial.momentum).length(),
+                momentum_scale,
+            ),
+            center_of_mass: relative(
+                (current.center_of_mass - initial.center_of_mass_at(current.time)).length(),
+                length_scale,
+            ),
+        }
+    }
+}
+
+// Absolute when there's nothing to compare against
+fn relative(change: f64, scale: f64) -> f64 {
+    if scale > 0.0 {
+        change / scale
+    } else {
+        change
+    }
+}
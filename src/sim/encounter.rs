@@ -0,0 +1,117 @@
+use glam::f64::DVec3;
+
+// How close two bodies have to pass to count as an encounter
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EncounterRadius {
+    Distance(f64),
+    // Multiple of the mutual Hill radius of the pair about the most massive body, which is never
+    // part of an encounter itself
+    Hill(f64),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Encounter {
+    // Time of closest approach
+    pub time: f64,
+    pub bodies: [usize; 2],
+    pub distance: f64,
+    pub speed: f64,
+}
+
+// Relative motion of two bodies over a step, interpolated by a cubic Hermite spline through their
+// relative position and velocity at either end, in the fraction τ of the step
+struct Hermite {
+    r0: DVec3,
+    v0: DVec3,
+    r1: DVec3,
+    v1: DVec3,
+}
+
+impl Hermite {
+    fn new((r0, v0): (DVec3, DVec3), (r1, v1): (DVec3, DVec3), step: f64) -> Self {
+        Self {
+            r0,
+            v0: step * v0,
+            r1,
+            v1: step * v1,
+        }
+    }
+
+    fn position(&self, t: f64) -> DVec3 {
+        let t2 = t * t;
+        let t3 = t2 * t;
+        (2.0 * t3 - 3.0 * t2 + 1.0) * self.r0
+            + (t3 - 2.0 * t2 + t) * self.v0
+            + (-2.0 * t3 + 3.0 * t2) * self.r1
+            + (t3 - t2) * self.v1
+    }
+
+    // Per unit τ
+    fn velocity(&self, t: f64) -> DVec3 {
+        let t2 = t * t;
+        (6.0 * t2 - 6.0 * t) * self.r0
+            + (3.0 * t2 - 4.0 * t + 1.0) * self.v0
+            + (-6.0 * t2 + 6.0 * t) * self.r1
+            + (3.0 * t2 - 2.0 * t) * self.v1
+    }
+}
+
+// Closest approach of two bodies over a step, from their relative position and velocity at
+// either end, found by bisecting on the sign of d|r|²/dτ. Returns the fraction of the step it
+// happens at, with the distance and speed there.
+pub(super) fn closest_approach(
+    start: (DVec3, DVec3),
+    end: (DVec3, DVec3),
+    step: f64,
+) -> Option<(f64, f64, f64)> {
+    let spline = Hermite::new(start, end, step);
+
+    // Only approaches that turn around within the step, so each is found exactly once
+    if spline.r0.dot(spline.v0) >= 0.0 || spline.r1.dot(spline.v1) < 0.0 {
+        return None;
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..64 {
+        let mid = (low + high) / 2.0;
+        if spline.position(mid).dot(spline.velocity(mid)) < 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    let t = (low + high) / 2.0;
+    Some((
+        t,
+        spline.position(t).length(),
+        spline.velocity(t).length() / step.abs(),
+    ))
+}
+
+// First touch of two bodies passing within `distance` of each other over a step, though apart at
+// either end, as the fraction of the step it happens at and their relative position there
+pub(super) fn contact(
+    start: (DVec3, DVec3),
+    end: (DVec3, DVec3),
+    step: f64,
+    distance: f64,
+) -> Option<(f64, DVec3)> {
+    let (closest, _, _) =
+        closest_approach(start, end, step).filter(|(_, closest, _)| *closest <= distance)?;
+    let spline = Hermite::new(start, end, step);
+
+    // Closing in all the way up to the closest approach
+    let (mut low, mut high) = (0.0, closest);
+    for _ in 0..64 {
+        let mid = (low + high) / 2.0;
+        if spline.position(mid).length() > distance {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    let t = (low + high) / 2.0;
+    Some((t, spline.position(t)))
+}
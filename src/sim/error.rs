@@ -0,0 +1,21 @@
+use super::collision::Collision;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum SimError {
+    Collision(Collision),
+}
+
+impl fmt::Display for SimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimError::Collision(collision) => write!(
+                f,
+                "bodies {} and {} collided at t = {}",
+                collision.bodies[0], collision.bodies[1], collision.time
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SimError {}
@@ -0,0 +1,25 @@
+use glam::f64::DVec3;
+
+// When a body counts as having left the system. Both the distance and the energy are taken
+// relative to the barycenter of every other body. The most massive body never leaves, since it's
+// what the rest would be leaving.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EscapeCriterion {
+    // Further away than this
+    Distance(f64),
+    // Positive two-body energy
+    Unbound,
+    // Unbound and further away than this
+    UnboundBeyond(f64),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Escape {
+    pub time: f64,
+    pub body: usize,
+    // Relative to the barycenter of the rest of the system, as it was removed
+    pub position: DVec3,
+    pub velocity: DVec3,
+    // Specific two-body energy
+    pub energy: f64,
+}
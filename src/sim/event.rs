@@ -0,0 +1,34 @@
+use super::{collision::Collision, crossing::Crossing, encounter::Encounter, escape::Escape};
+
+#[derive(Clone, Debug)]
+pub enum Event {
+    Collision(Collision),
+    Encounter(Encounter),
+    Escape(Escape),
+    Crossing(Crossing),
+}
+
+impl Event {
+    pub fn time(&self) -> f64 {
+        match self {
+            Event::Collision(collision) => collision.time,
+            Event::Encounter(encounter) => encounter.time,
+            Event::Escape(escape) => escape.time,
+            Event::Crossing(crossing) => crossing.time,
+        }
+    }
+
+    pub fn bodies(&self) -> &[usize] {
+        match self {
+            Event::Collision(collision) => &collision.bodies,
+            Event::Encounter(encounter) => &encounter.bodies,
+            Event::Escape(escape) => std::slice::from_ref(&escape.body),
+            // Of the whole system, rather than any one body
+            Event::Crossing(_) => &[],
+        }
+    }
+
+    pub fn involves(&self, id: usize) -> bool {
+        self.bodies().contains(&id)
+    }
+}
@@ -0,0 +1,127 @@
+use glam::f64::DVec3;
+
+// What a force sees of the system, indexed in the same order as `System::bodies`
+pub struct Bodies<'a> {
+    pub ids: &'a [usize],
+    pub masses: &'a [f64],
+    pub positions: &'a [DVec3],
+    pub velocities: &'a [DVec3],
+    pub g: f64,
+}
+
+impl Bodies<'_> {
+    pub fn index_of(&self, id: usize) -> Option<usize> {
+        self.ids.iter().position(|&i| i == id)
+    }
+}
+
+// An acceleration on top of gravity, evaluated by every integrator wherever it evaluates gravity.
+// It can depend on the whole state, including velocities.
+pub trait Force {
+    // Adds the acceleration of every body indexed by `targets`. Forces with a reaction, like the
+    // pull of an oblate primary, may add to other bodies too.
+    fn accelerate(&self, bodies: &Bodies, targets: &[usize], accelerations: &mut [DVec3]);
+}
+
+// Bodies a force acts on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Targets {
+    All,
+    // By id
+    Bodies(Vec<usize>),
+}
+
+impl Targets {
+    pub(super) fn indices(&self, ids: &[usize]) -> Vec<usize> {
+        match self {
+            Targets::All => (0..ids.len()).collect(),
+            Targets::Bodies(targets) => (0..ids.len())
+                .filter(|&i| targets.contains(&ids[i]))
+                .collect(),
+        }
+    }
+}
+
+// Radiation pressure from `source`, as a fraction `beta` of its gravity
+pub struct RadiationPressure {
+    pub source: usize,
+    pub beta: f64,
+}
+
+impl Force for RadiationPressure {
+    fn accelerate(&self, bodies: &Bodies, targets: &[usize], accelerations: &mut [DVec3]) {
+        let Some(source) = bodies.index_of(self.source) else {
+            return;
+        };
+        let mu = bodies.g * bodies.masses[source];
+
+        for &i in targets.iter().filter(|&&i| i != source) {
+            let x = bodies.positions[i] - bodies.positions[source];
+            accelerations[i] += self.beta * mu / x.length().powi(3) * x;
+        }
+    }
+}
+
+// Linear drag against gas moving with `frame`, damping relative velocities over `timescale`
+pub struct Drag {
+    pub frame: usize,
+    pub timescale: f64,
+}
+
+impl Force for Drag {
+    fn accelerate(&self, bodies: &Bodies, targets: &[usize], accelerations: &mut [DVec3]) {
+        let Some(frame) = bodies.index_of(self.frame) else {
+            return;
+        };
+
+        for &i in targets.iter().filter(|&&i| i != frame) {
+            let v = bodies.velocities[i] - bodies.velocities[frame];
+            accelerations[i] -= v / self.timescale;
+        }
+    }
+}
+
+// Constant acceleration
+pub struct Thrust {
+    pub acceleration: DVec3,
+}
+
+impl Force for Thrust {
+    fn accelerate(&self, _: &Bodies, targets: &[usize], accelerations: &mut [DVec3]) {
+        for &i in targets {
+            accelerations[i] += self.acceleration;
+        }
+    }
+}
+
+// Oblateness of `primary`, with its pole along z and its equatorial `radius`. The primary
+// recoils so momentum is conserved.
+pub struct J2 {
+    pub primary: usize,
+    pub j2: f64,
+    pub radius: f64,
+}
+
+impl Force for J2 {
+    fn accelerate(&self, bodies: &Bodies, targets: &[usize], accelerations: &mut [DVec3]) {
+        let Some(primary) = bodies.index_of(self.primary) else {
+            return;
+        };
+        let mass = bodies.masses[primary];
+        let mu = bodies.g * mass;
+
+        for &i in targets.iter().filter(|&&i| i != primary) {
+            let x = bodies.positions[i] - bodies.positions[primary];
+            let r2 = x.length_squared();
+            let z2 = 5.0 * x.z * x.z / r2;
+
+            let a = -1.5 * self.j2 * mu * self.radius * self.radius / (r2 * r2 * r2.sqrt())
+                * DVec3::new(x.x * (1.0 - z2), x.y * (1.0 - z2), x.z * (3.0 - z2));
+
+            accelerations[i] += a;
+            if mass > 0.0 {
+                accelerations[primary] -= bodies.masses[i] / mass * a;
+            }
+        }
+    }
+}
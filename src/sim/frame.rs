@@ -0,0 +1,13 @@
+// Reference frame the initial positions and velocities are shifted into when the sim is built,
+// and so the frame every output coordinate is in, up to any drift
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Frame {
+    // Left as given
+    AsGiven,
+    // Center of mass at rest at the origin
+    Barycentric,
+    // Most massive body at rest at the origin
+    Heliocentric,
+    // Body with the given id at rest at the origin
+    BodyCentric(usize),
+}
@@ -0,0 +1,85 @@
+use super::{
+    kernel,
+    octree::{Cell, Octree},
+    per_body, Gravity,
+};
+use glam::f64::DVec3;
+
+// Barnes-Hut tree code. Bodies are sorted into an octree, and a cell is treated as a single
+// point mass at its center of mass once its size is less than `theta` times its distance.
+pub struct BarnesHut {
+    theta: f64,
+}
+
+impl BarnesHut {
+    pub fn new(theta: f64) -> Self {
+        Self { theta }
+    }
+
+    // Acceleration at `position` per unit gravitational constant, skipping the body at `index`
+    fn acceleration(
+        &self,
+        tree: &Octree,
+        index: usize,
+        position: DVec3,
+        positions: &[DVec3],
+        masses: &[f64],
+        softening2: f64,
+    ) -> DVec3 {
+        let mut acceleration = DVec3::ZERO;
+        let mut stack = if tree.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+
+        while let Some(n) = stack.pop() {
+            let node = &tree.nodes[n];
+            if node.mass == 0.0 {
+                continue;
+            }
+
+            match &node.cell {
+                Cell::Leaf(range) => {
+                    for i in &tree.order[range.clone()] {
+                        if *i != index {
+                            let x = positions[*i] - position;
+                            acceleration += masses[*i] * kernel(x, softening2) * x;
+                        }
+                    }
+                }
+                Cell::Internal(children) => {
+                    let x = node.center_of_mass - position;
+                    let distance = x.length();
+                    let inside = (position - node.center).abs().max_element() <= node.half_size;
+
+                    if !inside && 2.0 * node.half_size < self.theta * distance {
+                        acceleration += node.mass * kernel(x, softening2) * x;
+                    } else {
+                        stack.extend(children);
+                    }
+                }
+            }
+        }
+
+        acceleration
+    }
+}
+
+impl Gravity for BarnesHut {
+    fn accelerations(
+        &self,
+        positions: &[DVec3],
+        masses: &[f64],
+        g: f64,
+        softening: f64,
+    ) -> Vec<DVec3> {
+        let tree = Octree::new(positions, masses, 1);
+        let softening2 = softening * softening;
+
+        per_body(positions.len(), |index| {
+            let position = positions[index];
+            self.acceleration(&tree, index, position, positions, masses, softening2) * g
+        })
+    }
+}
@@ -0,0 +1,77 @@
+use super::{kernel, per_body, Gravity};
+use glam::f64::DVec3;
+
+// Direct summation over every pair of bodies
+pub struct Direct;
+
+impl Direct {
+    // Kept free of branches, and summed over independent lanes so it can be vectorized
+    fn sum(position: DVec3, positions: &[DVec3], masses: &[f64], softening2: f64) -> DVec3 {
+        const LANES: usize = 4;
+
+        let term = |other_position: DVec3, mass: f64| {
+            let x = other_position - position;
+            mass * kernel(x, softening2) * x
+        };
+
+        let mut lanes = [DVec3::ZERO; LANES];
+        let chunks = positions
+            .chunks_exact(LANES)
+            .zip(masses.chunks_exact(LANES));
+        for (positions, masses) in chunks {
+            for lane in 0..LANES {
+                lanes[lane] += term(positions[lane], masses[lane]);
+            }
+        }
+
+        let remainder = positions.len() - positions.len() % LANES;
+        let tail = positions[remainder..]
+            .iter()
+            .zip(&masses[remainder..])
+            .fold(DVec3::ZERO, |sum, (r, m)| sum + term(*r, *m));
+
+        lanes.iter().fold(tail, |sum, lane| sum + *lane)
+    }
+}
+
+impl Gravity for Direct {
+    fn accelerations(
+        &self,
+        positions: &[DVec3],
+        masses: &[f64],
+        g: f64,
+        softening: f64,
+    ) -> Vec<DVec3> {
+        let softening2 = softening * softening;
+
+        per_body(positions.len(), |index| {
+            let position = positions[index];
+
+            // Skip the body itself by summing the others on either side of it
+            let before = Self::sum(position, &positions[..index], &masses[..index], softening2);
+            let after = Self::sum(
+                position,
+                &positions[index + 1..],
+                &masses[index + 1..],
+                softening2,
+            );
+
+            g * (before + after)
+        })
+    }
+
+    fn accelerations_on(
+        &self,
+        targets: &[DVec3],
+        positions: &[DVec3],
+        masses: &[f64],
+        g: f64,
+        softening: f64,
+    ) -> Vec<DVec3> {
+        let softening2 = softening * softening;
+
+        per_body(targets.len(), |index| {
+            g * Self::sum(targets[index], positions, masses, softening2)
+        })
+    }
+}
@@ -0,0 +1,338 @@
+use super::{
+    kernel,
+    octree::{Cell, Octree},
+    per_body, Gravity,
+};
+use glam::f64::DVec3;
+
+// Cells interact through their expansions once their combined radii are less than this fraction
+// of the distance between them
+const THETA: f64 = 0.5;
+const LEAF_SIZE: usize = 16;
+
+// Fast multipole method with Cartesian Taylor expansions (Dehnen 2002).
+//
+// Every cell carries the multipole moments of its bodies and a local expansion of the potential,
+// both about its center of mass. Well separated cells interact once
+// through their expansions, found by a walk over pairs of cells, and the local expansions are
+// then passed down the tree to the bodies. Expansions are truncated at total degree `order`, and
+// forces follow from their gradient, so are one degree lower. Softening only applies between
+// neighboring bodies, where they interact directly.
+pub struct Fmm {
+    order: usize,
+    // Multi-indices of every term, by increasing degree
+    indices: Vec<[usize; 3]>,
+    lookup: Vec<usize>,
+    // (term, source term, offset term, weight) sums for each translation
+    multipole_to_multipole: Vec<(usize, usize, usize, f64)>,
+    multipole_to_local: Vec<(usize, usize, usize, f64)>,
+    local_to_local: Vec<(usize, usize, usize, f64)>,
+}
+
+// Highest order expansions are taken to. Setup grows with the square of the number of terms, a
+// thousand at this order.
+pub const MAX_ORDER: usize = 16;
+
+impl Fmm {
+    // Panics if `order` is 0, which would leave no far-field force at all, or above `MAX_ORDER`
+    pub fn new(order: usize) -> Self {
+        assert!(order >= 1, "FMM expansions need an order of at least 1");
+        assert!(
+            order <= MAX_ORDER,
+            "FMM expansions go up to order {MAX_ORDER}, not {order}"
+        );
+
+        let mut indices = vec![];
+        for degree in 0..=order {
+            for i in (0..=degree).rev() {
+                for j in (0..=degree - i).rev() {
+                    indices.push([i, j, degree - i - j]);
+                }
+            }
+        }
+
+        let side = order + 1;
+        let mut lookup = vec![usize::MAX; side * side * side];
+        for (n, [i, j, k]) in indices.iter().enumerate() {
+            lookup[(i * side + j) * side + k] = n;
+        }
+
+        let mut fmm = Self {
+            order,
+            indices,
+            lookup,
+            multipole_to_multipole: vec![],
+            multipole_to_local: vec![],
+            local_to_local: vec![],
+        };
+
+        for (n, outer) in fmm.indices.iter().enumerate() {
+            for (k, inner) in fmm.indices.iter().enumerate() {
+                if (0..3).all(|i| inner[i] <= outer[i]) {
+                    let offset = fmm.index(sub(*outer, *inner));
+                    let weight = binomial(*outer, *inner);
+
+                    // Q'_n = Σ C(n, k) Q_k s^(n - k)
+                    fmm.multipole_to_multipole.push((n, k, offset, weight));
+                    // L'_k = Σ C(n, k) L_n t^(n - k)
+                    fmm.local_to_local.push((k, n, offset, weight));
+                }
+
+                if degree(*outer) + degree(*inner) <= order {
+                    // L_n = -Σ (-1)^|k| C(n + k, k) Q_k a_(n + k)
+                    let sum = add(*outer, *inner);
+                    let sign = if degree(*inner).is_multiple_of(2) {
+                        -1.0
+                    } else {
+                        1.0
+                    };
+                    fmm.multipole_to_local.push((
+                        n,
+                        k,
+                        fmm.index(sum),
+                        sign * binomial(sum, *inner),
+                    ));
+                }
+            }
+        }
+
+        fmm
+    }
+
+    fn index(&self, [i, j, k]: [usize; 3]) -> usize {
+        let side = self.order + 1;
+        self.lookup[(i * side + j) * side + k]
+    }
+
+    // Monomials x^k of every term
+    fn powers(&self, x: DVec3) -> Vec<f64> {
+        let axis = |x: f64| {
+            (0..=self.order)
+                .scan(1.0, |power, _| {
+                    let current = *power;
+                    *power *= x;
+                    Some(current)
+                })
+                .collect::<Vec<_>>()
+        };
+        let (px, py, pz) = (axis(x.x), axis(x.y), axis(x.z));
+
+        self.indices
+            .iter()
+            .map(|[i, j, k]| px[*i] * py[*j] * pz[*k])
+            .collect()
+    }
+
+    // Taylor coefficients D^k(1 / |r|) / k! of every term, through the recurrence
+    // |k| r² a_k + (2|k| - 1) Σ r_i a_(k - e_i) + (|k| - 1) Σ a_(k - 2e_i) = 0
+    fn taylor(&self, r: DVec3, a: &mut [f64]) {
+        let r2 = r.length_squared();
+        a[0] = 1.0 / r2.sqrt();
+
+        for (n, k) in self.indices.iter().enumerate().skip(1) {
+            let d = degree(*k) as f64;
+            let mut sum = 0.0;
+
+            for i in 0..3 {
+                if k[i] >= 1 {
+                    let mut lower = *k;
+                    lower[i] -= 1;
+                    sum += (2.0 * d - 1.0) * r[i] * a[self.index(lower)];
+
+                    if k[i] >= 2 {
+                        lower[i] -= 1;
+                        sum += (d - 1.0) * a[self.index(lower)];
+                    }
+                }
+            }
+
+            a[n] = -sum / (d * r2);
+        }
+    }
+
+    fn translate(
+        terms: &[(usize, usize, usize, f64)],
+        source: &[f64],
+        offset: &[f64],
+        target: &mut [f64],
+    ) {
+        for (n, k, o, weight) in terms {
+            target[*n] += weight * source[*k] * offset[*o];
+        }
+    }
+
+    // Multipole moments of every cell, along with the radius of the sphere about its center of
+    // mass holding all its bodies
+    fn multipoles(
+        &self,
+        tree: &Octree,
+        positions: &[DVec3],
+        masses: &[f64],
+    ) -> (Vec<Vec<f64>>, Vec<f64>) {
+        let mut multipoles = vec![vec![0.0; self.indices.len()]; tree.nodes.len()];
+        let mut radii = vec![0.0_f64; tree.nodes.len()];
+
+        // Children always come after their parent, so walking backwards visits them first
+        for n in (0..tree.nodes.len()).rev() {
+            let node = &tree.nodes[n];
+
+            match &node.cell {
+                Cell::Leaf(range) => {
+                    for i in &tree.order[range.clone()] {
+                        let x = positions[*i] - node.center_of_mass;
+                        radii[n] = radii[n].max(x.length());
+
+                        for (q, p) in multipoles[n].iter_mut().zip(self.powers(x)) {
+                            *q += masses[*i] * p;
+                        }
+                    }
+                }
+                Cell::Internal(children) => {
+                    let mut multipole = vec![0.0; self.indices.len()];
+                    for c in children {
+                        let x = tree.nodes[*c].center_of_mass - node.center_of_mass;
+                        radii[n] = radii[n].max(x.length() + radii[*c]);
+
+                        let offset = self.powers(x);
+                        Self::translate(
+                            &self.multipole_to_multipole,
+                            &multipoles[*c],
+                            &offset,
+                            &mut multipole,
+                        );
+                    }
+                    multipoles[n] = multipole;
+                }
+            }
+        }
+
+        (multipoles, radii)
+    }
+}
+
+impl Gravity for Fmm {
+    fn accelerations(
+        &self,
+        positions: &[DVec3],
+        masses: &[f64],
+        g: f64,
+        softening: f64,
+    ) -> Vec<DVec3> {
+        let softening2 = softening * softening;
+        if positions.is_empty() {
+            return vec![];
+        }
+
+        let tree = Octree::new(positions, masses, LEAF_SIZE);
+        let (multipoles, radii) = self.multipoles(&tree, positions, masses);
+        let mut locals = vec![vec![0.0; self.indices.len()]; tree.nodes.len()];
+        // Bodies of the leaves every leaf interacts with directly, in the order the walk reached
+        // them
+        let mut neighbors = vec![vec![]; tree.nodes.len()];
+
+        let mut taylor = vec![0.0; self.indices.len()];
+        let mut pairs = vec![(0, 0)];
+        while let Some((target, source)) = pairs.pop() {
+            let (a, b) = (&tree.nodes[target], &tree.nodes[source]);
+            if b.mass == 0.0 {
+                continue;
+            }
+
+            let (ra, rb) = (radii[target], radii[source]);
+            let x = a.center_of_mass - b.center_of_mass;
+
+            match (&a.cell, &b.cell) {
+                _ if target != source && ra + rb < THETA * x.length() => {
+                    self.taylor(x, &mut taylor);
+                    Self::translate(
+                        &self.multipole_to_local,
+                        &multipoles[source],
+                        &taylor,
+                        &mut locals[target],
+                    );
+                }
+                (Cell::Leaf(_), Cell::Leaf(sources)) => {
+                    neighbors[target].push(sources.clone());
+                }
+                (Cell::Internal(children), Cell::Leaf(_)) => {
+                    pairs.extend(children.iter().map(|c| (*c, source)));
+                }
+                (Cell::Internal(children), Cell::Internal(_)) if ra >= rb => {
+                    pairs.extend(children.iter().map(|c| (*c, source)));
+                }
+                (_, Cell::Internal(children)) => {
+                    pairs.extend(children.iter().map(|c| (target, *c)));
+                }
+            }
+        }
+
+        // Parents always come before their children, so walking forwards passes the local
+        // expansions all the way down
+        let mut leaves = vec![0; positions.len()];
+        for n in 0..tree.nodes.len() {
+            let node = &tree.nodes[n];
+
+            match &node.cell {
+                Cell::Internal(children) => {
+                    for c in children {
+                        let offset =
+                            self.powers(tree.nodes[*c].center_of_mass - node.center_of_mass);
+                        let (parent, child) = locals.split_at_mut(*c);
+                        Self::translate(&self.local_to_local, &parent[n], &offset, &mut child[0]);
+                    }
+                }
+                Cell::Leaf(range) => {
+                    for i in &tree.order[range.clone()] {
+                        leaves[*i] = n;
+                    }
+                }
+            }
+        }
+
+        per_body(positions.len(), |i| {
+            let n = leaves[i];
+            let mut acceleration = DVec3::ZERO;
+
+            for sources in &neighbors[n] {
+                for j in &tree.order[sources.clone()] {
+                    if i != *j {
+                        let x = positions[*j] - positions[i];
+                        acceleration += masses[*j] * kernel(x, softening2) * x;
+                    }
+                }
+            }
+
+            // a = -∇φ = -Σ L_n ∇(e^n)
+            let powers = self.powers(positions[i] - tree.nodes[n].center_of_mass);
+            for (l, k) in locals[n].iter().zip(&self.indices) {
+                for axis in 0..3 {
+                    if k[axis] > 0 {
+                        let mut lower = *k;
+                        lower[axis] -= 1;
+                        acceleration[axis] -= l * k[axis] as f64 * powers[self.index(lower)];
+                    }
+                }
+            }
+
+            acceleration * g
+        })
+    }
+}
+
+fn degree(k: [usize; 3]) -> usize {
+    k[0] + k[1] + k[2]
+}
+
+fn add(a: [usize; 3], b: [usize; 3]) -> [usize; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [usize; 3], b: [usize; 3]) -> [usize; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn binomial(n: [usize; 3], k: [usize; 3]) -> f64 {
+    (0..3)
+        .map(|i| (0..k[i]).fold(1.0, |c, j| c * (n[i] - j) as f64 / (j + 1) as f64))
+        .product()
+}
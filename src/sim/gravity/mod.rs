@@ -0,0 +1,87 @@
+use glam::f64::DVec3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GravityType {
+    Direct,
+    BarnesHut { theta: f64 },
+    // Expansions to `order`, from 1 to `fmm::MAX_ORDER`
+    Fmm { order: usize },
+}
+
+impl GravityType {
+    pub fn gravity(&self) -> Box<dyn Gravity> {
+        match self {
+            GravityType::Direct => Box::new(direct::Direct),
+            GravityType::BarnesHut { theta } => Box::new(barnes_hut::BarnesHut::new(*theta)),
+            GravityType::Fmm { order } => Box::new(fmm::Fmm::new(*order)),
+        }
+    }
+}
+
+// Evaluates the gravitational acceleration of every body due to all the others, with Plummer
+// softening: a = G m x / (|x|² + ε²)^(3/2)
+pub trait Gravity {
+    fn accelerations(
+        &self,
+        positions: &[DVec3],
+        masses: &[f64],
+        g: f64,
+        softening: f64,
+    ) -> Vec<DVec3>;
+
+    // Accelerations at `targets` due to every body, none of which is among the targets. This is
+    // how massless test particles feel the massive bodies, in O(targets × bodies).
+    fn accelerations_on(
+        &self,
+        targets: &[DVec3],
+        positions: &[DVec3],
+        masses: &[f64],
+        g: f64,
+        softening: f64,
+    ) -> Vec<DVec3> {
+        let softening2 = softening * softening;
+
+        per_body(targets.len(), |index| {
+            let target = targets[index];
+            g * positions
+                .iter()
+                .zip(masses)
+                .fold(DVec3::ZERO, |sum, (position, mass)| {
+                    let x = *position - target;
+                    sum + *mass * kernel(x, softening2) * x
+                })
+        })
+    }
+}
+
+// 1 / (|x|² + ε²)^(3/2)
+fn kernel(x: DVec3, softening2: f64) -> f64 {
+    let r2 = x.length_squared() + softening2;
+    1.0 / (r2 * r2.sqrt())
+}
+
+// Fewest bodies worth the overhead of spreading over threads
+#[cfg(feature = "parallel")]
+const MIN_PARALLEL: usize = 256;
+
+// Evaluates `acceleration` for every body, spread over threads with the `parallel` feature once
+// there are enough of them. Each body is still summed on a single thread in a fixed order, so the
+// results are identical whatever the number of threads.
+fn per_body<F>(len: usize, acceleration: F) -> Vec<DVec3>
+where
+    F: Fn(usize) -> DVec3 + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    if len >= MIN_PARALLEL {
+        use rayon::prelude::*;
+        return (0..len).into_par_iter().map(acceleration).collect();
+    }
+
+    (0..len).map(acceleration).collect()
+}
+
+pub mod barnes_hut;
+pub mod direct;
+pub mod fmm;
+
+mod octree;
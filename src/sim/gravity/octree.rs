@@ -0,0 +1,120 @@
+use glam::f64::DVec3;
+use std::ops::Range;
+
+// Bodies closer together than the smallest cell at this depth share a leaf
+const MAX_DEPTH: usize = 32;
+
+pub(super) enum Cell {
+    Internal(Vec<usize>),
+    // Range of `Octree::order` holding the bodies of the leaf
+    Leaf(Range<usize>),
+}
+
+pub(super) struct Node {
+    pub(super) center: DVec3,
+    pub(super) half_size: f64,
+    pub(super) mass: f64,
+    pub(super) center_of_mass: DVec3,
+    pub(super) cell: Cell,
+}
+
+pub(super) struct Octree {
+    pub(super) nodes: Vec<Node>,
+    // Body indices, grouped so every node covers a contiguous range
+    pub(super) order: Vec<usize>,
+    leaf_size: usize,
+}
+
+impl Octree {
+    // Leaves hold at most `leaf_size` bodies, unless they are too close together to split
+    pub(super) fn new(positions: &[DVec3], masses: &[f64], leaf_size: usize) -> Self {
+        let (min, max) = positions.iter().fold(
+            (DVec3::splat(f64::INFINITY), DVec3::splat(f64::NEG_INFINITY)),
+            |(min, max), r| (min.min(*r), max.max(*r)),
+        );
+        let center = (min + max) / 2.0;
+        // Pad the root slightly so bodies on its boundary still fall inside
+        let half_size = (max - min).max_element() / 2.0 * (1.0 + 1e-9);
+
+        let mut tree = Self {
+            nodes: vec![],
+            order: (0..positions.len()).collect(),
+            leaf_size,
+        };
+
+        if !positions.is_empty() {
+            tree.insert(0..positions.len(), center, half_size, 0, positions, masses);
+        }
+
+        tree
+    }
+
+    fn insert(
+        &mut self,
+        range: Range<usize>,
+        center: DVec3,
+        half_size: f64,
+        depth: usize,
+        positions: &[DVec3],
+        masses: &[f64],
+    ) -> usize {
+        let (mass, moment) = self.order[range.clone()]
+            .iter()
+            .fold((0.0, DVec3::ZERO), |(mass, moment), i| {
+                (mass + masses[*i], moment + masses[*i] * positions[*i])
+            });
+        let center_of_mass = if mass > 0.0 { moment / mass } else { center };
+
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            center,
+            half_size,
+            mass,
+            center_of_mass,
+            cell: Cell::Leaf(range.clone()),
+        });
+
+        if range.len() <= self.leaf_size || depth == MAX_DEPTH {
+            return index;
+        }
+
+        self.order[range.clone()].sort_by_key(|i| octant(positions[*i], center));
+
+        let mut children = vec![];
+        let mut start = range.start;
+        while start < range.end {
+            let o = octant(positions[self.order[start]], center);
+            let end = start
+                + self.order[start..range.end]
+                    .iter()
+                    .take_while(|i| octant(positions[**i], center) == o)
+                    .count();
+
+            let offset = DVec3::new(
+                if o & 1 != 0 { 1.0 } else { -1.0 },
+                if o & 2 != 0 { 1.0 } else { -1.0 },
+                if o & 4 != 0 { 1.0 } else { -1.0 },
+            );
+            children.push(self.insert(
+                start..end,
+                center + half_size / 2.0 * offset,
+                half_size / 2.0,
+                depth + 1,
+                positions,
+                masses,
+            ));
+
+            start = end;
+        }
+
+        self.nodes[index].cell = Cell::Internal(children);
+
+        index
+    }
+}
+
+fn octant(position: DVec3, center: DVec3) -> u8 {
+    (position.x >= center.x) as u8
+        | ((position.y >= center.y) as u8) << 1
+        | ((position.z >= center.z) as u8) << 2
+}
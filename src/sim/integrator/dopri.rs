@@ -0,0 +1,163 @@
+use super::{substeps, Attempt, Integrator, Model, State, Stats};
+use crate::sim::checkpoint::{CheckpointError, Decoder, Encoder};
+use glam::f64::DVec3;
+
+// Dormand-Prince 5(4) tableau. Gravity doesn't depend on time, so the nodes are left out.
+const A: [&[f64]; 7] = [
+    &[],
+    &[1.0 / 5.0],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+    &[
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+    ],
+    &[
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+    ],
+    &[
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+// Difference between the 5th and embedded 4th order weights
+const E: [f64; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 5.0;
+
+// Adaptive embedded Runge-Kutta. Sub-steps until the requested step is covered, keeping the local
+// error of every sub-step within `atol + rtol * |y|`.
+pub struct DormandPrince {
+    atol: f64,
+    rtol: f64,
+    // Size of the next trial sub-step, carried over between calls
+    trial: Option<f64>,
+    stats: Stats,
+}
+
+impl DormandPrince {
+    pub fn new(atol: f64, rtol: f64) -> Self {
+        Self {
+            atol,
+            rtol,
+            trial: None,
+            stats: Stats::default(),
+        }
+    }
+
+    // RMS of the error estimate, scaled by the tolerance of each component
+    fn error(&self, start: &State, end: &State, ks: &[&State], step: f64) -> f64 {
+        let estimate = State {
+            positions: vec![DVec3::ZERO; start.len()],
+            velocities: vec![DVec3::ZERO; start.len()],
+        }
+        .stage(ks, &E, step);
+
+        let components = start
+            .positions
+            .iter()
+            .chain(&start.velocities)
+            .zip(end.positions.iter().chain(&end.velocities))
+            .zip(estimate.positions.iter().chain(&estimate.velocities))
+            .flat_map(|((y0, y1), e)| {
+                let scale = self.atol * DVec3::ONE + self.rtol * y0.abs().max(y1.abs());
+                // Exact, rather than 0/0 for a component that stays at zero under a purely
+                // relative tolerance
+                e.to_array()
+                    .into_iter()
+                    .zip(scale.to_array())
+                    .map(|(e, scale)| if e == 0.0 { 0.0 } else { e / scale })
+            });
+
+        let (sum, n) = components.fold((0.0, 0), |(sum, n), x| (sum + x * x, n + 1));
+
+        if n == 0 {
+            0.0
+        } else {
+            (sum / n as f64).sqrt()
+        }
+    }
+}
+
+impl Integrator for DormandPrince {
+    fn step(&mut self, state: &mut State, model: &dyn Model, step: f64) {
+        let mut k1 = state.derivative(model);
+        let (mut trial, mut stats) = (self.trial, self.stats);
+
+        substeps(step, &mut trial, &mut stats, |h, forced| {
+            let mut ks = vec![k1.clone()];
+            for a in A.iter().skip(1) {
+                let stage = state.stage(&ks.iter().collect::<Vec<_>>(), a, h);
+                ks.push(stage.derivative(model));
+            }
+
+            let ks_ref = ks.iter().collect::<Vec<_>>();
+            // The last stage is evaluated at the 5th order solution
+            let end = state.stage(&ks_ref[..6], A[6], h);
+            let error = self.error(state, &end, &ks_ref, h);
+
+            // A non-finite error, from a singular force, can't be compared against the
+            // tolerance, so the sub-step shrinks as fast as it's allowed to. If forced, the state
+            // may be no longer finite either.
+            let factor = if !error.is_finite() {
+                MIN_FACTOR
+            } else if error == 0.0 {
+                MAX_FACTOR
+            } else {
+                (SAFETY * error.powf(-0.2)).clamp(MIN_FACTOR, MAX_FACTOR)
+            };
+
+            if error <= 1.0 || forced {
+                *state = end;
+                k1 = ks.pop().unwrap();
+
+                if state.is_finite() {
+                    Attempt::Accepted { next: h * factor }
+                } else {
+                    Attempt::Abandoned
+                }
+            } else {
+                Attempt::Rejected {
+                    next: h * factor.min(1.0),
+                }
+            }
+        });
+
+        (self.trial, self.stats) = (trial, stats);
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        Some(self.stats)
+    }
+
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.option_f64(self.trial);
+        self.stats.save(encoder);
+    }
+
+    fn load(&mut self, decoder: &mut Decoder, _len: usize) -> Result<(), CheckpointError> {
+        self.trial = decoder.option_f64()?;
+        self.stats = Stats::load(decoder)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use super::{Integrator, Model, State};
+
+// Explicit (forward) Euler, first order and not symplectic
+pub struct Euler;
+
+impl Integrator for Euler {
+    fn step(&mut self, state: &mut State, model: &dyn Model, step: f64) {
+        let accelerations = model.accelerations(state);
+
+        state.drift(step);
+        state.kick(&accelerations, step);
+    }
+}
+
+// Semi-implicit (symplectic) Euler, kicks with the old positions then drifts with the new
+// velocities
+pub struct SemiImplicitEuler;
+
+impl Integrator for SemiImplicitEuler {
+    fn step(&mut self, state: &mut State, model: &dyn Model, step: f64) {
+        let accelerations = model.accelerations(state);
+
+        state.kick(&accelerations, step);
+        state.drift(step);
+    }
+}
@@ -0,0 +1,335 @@
+use super::{substeps, Attempt, Integrator, Model, State, Stats};
+use crate::sim::checkpoint::{CheckpointError, Decoder, Encoder, VEC3};
+use glam::f64::DVec3;
+
+// Gauss-Radau spacings on [0, 1]
+const H: [f64; 8] = [
+    0.0,
+    0.056_262_560_536_922_15,
+    0.180_240_691_736_892_36,
+    0.352_624_717_113_169_6,
+    0.547_153_626_330_555_4,
+    0.734_210_177_215_410_5,
+    0.885_320_946_839_095_8,
+    0.977_520_613_561_287_5,
+];
+
+const SAFETY: f64 = 0.25;
+const MAX_ITERATIONS: usize = 12;
+
+type Coefficients = [DVec3; 7];
+
+// 15th order implicit Gauss-Radau integrator with adaptive steps (Rein & Spiegel 2015).
+//
+// Over a step the acceleration is fit by a polynomial a(τ) = a0 + Σ b_k τ^(k+1), τ in [0, 1],
+// which is refined by predictor-corrector iterations through the Gauss-Radau nodes. The last
+// coefficient estimates the error of the step, and is used to pick the next step so it stays
+// below `epsilon`.
+pub struct Ias15 {
+    epsilon: f64,
+    trial: Option<f64>,
+    // Converts the coefficients of the Newton form a(τ) = a0 + Σ g_k τ Π_{i=1..k}(τ - h_i)
+    // into monomial ones, and back
+    to_b: [[f64; 7]; 7],
+    to_g: [[f64; 7]; 7],
+    // Monomial coefficients of every body, for steps of size `scale`
+    b: Vec<Coefficients>,
+    scale: f64,
+    // Compensated summation error of the positions and velocities
+    position_error: Vec<DVec3>,
+    velocity_error: Vec<DVec3>,
+    stats: Stats,
+}
+
+impl Ias15 {
+    pub fn new(epsilon: f64) -> Self {
+        let mut to_b = [[0.0; 7]; 7];
+
+        // Expand τ Π_{i=1..k}(τ - h_i) one factor at a time, `poly[j]` holding the coefficient
+        // of τ^(j + 1)
+        let mut poly = [0.0; 7];
+        poly[0] = 1.0;
+        for k in 0..7 {
+            for (j, coefficient) in poly.iter().enumerate() {
+                to_b[j][k] = *coefficient;
+            }
+
+            let mut next = [0.0; 7];
+            for j in 0..7 {
+                next[j] = -H[k + 1] * poly[j] + if j > 0 { poly[j - 1] } else { 0.0 };
+            }
+            poly = next;
+        }
+
+        // `to_b` is upper unitriangular, so its inverse follows by back substitution
+        let mut to_g = [[0.0; 7]; 7];
+        for k in 0..7 {
+            let mut column = [0.0; 7];
+            column[k] = 1.0;
+            for j in (0..k).rev() {
+                column[j] = -((j + 1)..=k).map(|i| to_b[j][i] * column[i]).sum::<f64>();
+            }
+
+            for (row, c) in to_g.iter_mut().zip(column) {
+                row[k] = c;
+            }
+        }
+
+        Self {
+            epsilon,
+            trial: None,
+            to_b,
+            to_g,
+            b: vec![],
+            scale: 0.0,
+            position_error: vec![],
+            velocity_error: vec![],
+            stats: Stats::default(),
+        }
+    }
+
+    fn convert(matrix: &[[f64; 7]; 7], coefficients: &Coefficients) -> Coefficients {
+        let mut converted = [DVec3::ZERO; 7];
+        for (j, row) in matrix.iter().enumerate() {
+            for (k, weight) in row.iter().enumerate().skip(j) {
+                converted[j] += *weight * coefficients[k];
+            }
+        }
+        converted
+    }
+
+    // Position and velocity increments at τ over a step
+    fn increment(b: &Coefficients, a0: DVec3, v0: DVec3, tau: f64, step: f64) -> (DVec3, DVec3) {
+        let mut dx = a0 / 2.0;
+        let mut dv = a0;
+        let mut power = tau;
+
+        for (k, b) in b.iter().enumerate() {
+            let n = (k + 2) as f64;
+            dx += power / (n * (n + 1.0)) * *b;
+            dv += power / n * *b;
+            power *= tau;
+        }
+
+        (step * tau * (v0 + step * tau * dx), step * tau * dv)
+    }
+
+    fn resize(&mut self, len: usize) {
+        if self.b.len() != len {
+            self.b = vec![[DVec3::ZERO; 7]; len];
+            self.position_error = vec![DVec3::ZERO; len];
+            self.velocity_error = vec![DVec3::ZERO; len];
+        }
+    }
+
+    // Stretches the acceleration polynomial of every body to a step of `step`. With `shift`, the
+    // polynomial is also moved to start at the end of the current step.
+    fn predict(&mut self, step: f64, shift: bool) {
+        let q = step / self.scale;
+
+        for b in self.b.iter_mut() {
+            let mut predicted = [DVec3::ZERO; 7];
+            let mut power = q;
+
+            for (k, p) in predicted.iter_mut().enumerate() {
+                if shift {
+                    for (j, b) in b.iter().enumerate().skip(k) {
+                        *p += binomial(j + 1, k + 1) * *b;
+                    }
+                } else {
+                    *p = b[k];
+                }
+                *p *= power;
+                power *= q;
+            }
+
+            *b = predicted;
+        }
+
+        self.scale = step;
+    }
+
+    // Attempts a single step. A forced step is accepted whatever its error.
+    fn attempt(
+        &mut self,
+        state: &mut State,
+        model: &dyn Model,
+        step: f64,
+        forced: bool,
+    ) -> Attempt {
+        if self.scale != 0.0 && self.scale != step {
+            self.predict(step, false);
+        }
+        self.scale = step;
+
+        let a0 = model.accelerations(state);
+        let mut g = self
+            .b
+            .iter()
+            .map(|b| Self::convert(&self.to_g, b))
+            .collect::<Vec<_>>();
+
+        let mut substate = state.clone();
+        let mut last_correction = f64::INFINITY;
+        let mut max_acceleration = 0.0_f64;
+
+        for iteration in 0..MAX_ITERATIONS {
+            let mut max_correction = 0.0_f64;
+            max_acceleration = 0.0;
+
+            for (node, h) in H.iter().enumerate().skip(1) {
+                for (i, a) in a0.iter().enumerate() {
+                    let (dx, dv) = Self::increment(&self.b[i], *a, state.velocities[i], *h, step);
+                    // From the start of the step with its compensation applied
+                    substate.positions[i] = state.positions[i] + (dx - self.position_error[i]);
+                    substate.velocities[i] = state.velocities[i] + (dv - self.velocity_error[i]);
+                }
+
+                let accelerations = model.accelerations(&substate);
+
+                for (i, a) in accelerations.iter().enumerate() {
+                    // Divided differences through the nodes up to this one
+                    let mut gk = (*a - a0[i]) / *h;
+                    for k in 0..node - 1 {
+                        gk = (gk - g[i][k]) / (h - H[k + 1]);
+                    }
+                    g[i][node - 1] = gk;
+
+                    let b6 = self.b[i][6];
+                    self.b[i] = Self::convert(&self.to_b, &g[i]);
+
+                    if node == 7 {
+                        max_correction =
+                            max_correction.max((self.b[i][6] - b6).abs().max_element());
+                        max_acceleration = max_acceleration.max(a.abs().max_element());
+                    }
+                }
+            }
+
+            // Iterate until the corrections are at round-off, or stop improving
+            let correction = max_correction / max_acceleration;
+            if correction.is_nan()
+                || correction <= 1e-16
+                || (iteration > 1 && correction >= last_correction)
+            {
+                break;
+            }
+            last_correction = correction;
+        }
+
+        let b6 = self
+            .b
+            .iter()
+            .map(|b| b[6].abs().max_element())
+            .fold(0.0, f64::max);
+        let error = b6 / max_acceleration;
+
+        let factor = (self.epsilon / error).powf(1.0 / 7.0);
+        let factor = if factor.is_finite() {
+            factor.min(1.0 / SAFETY)
+        } else {
+            1.0 / SAFETY
+        };
+
+        // Where every acceleration cancels to round-off, so does `b6`, and no step brings the
+        // error down. A step over which it can't move any body by more than round-off in its
+        // position is good enough anyway, and needn't shrink.
+        let scale = state
+            .positions
+            .iter()
+            .map(|x| x.abs().max_element())
+            .fold(0.0, f64::max);
+        let round_off = b6 * step * step <= f64::EPSILON * scale;
+        let factor = if round_off { factor.max(1.0) } else { factor };
+
+        if factor < SAFETY && !forced {
+            return Attempt::Rejected {
+                next: step * factor,
+            };
+        }
+
+        for (i, a) in a0.iter().enumerate() {
+            let (dx, dv) = Self::increment(&self.b[i], *a, state.velocities[i], 1.0, step);
+            kahan(&mut state.positions[i], &mut self.position_error[i], dx);
+            kahan(&mut state.velocities[i], &mut self.velocity_error[i], dv);
+        }
+        self.predict(step, true);
+
+        Attempt::Accepted {
+            next: step * factor,
+        }
+    }
+}
+
+impl Integrator for Ias15 {
+    fn step(&mut self, state: &mut State, model: &dyn Model, step: f64) {
+        self.resize(state.len());
+        let (mut trial, mut stats) = (self.trial, self.stats);
+
+        substeps(step, &mut trial, &mut stats, |h, forced| {
+            self.attempt(state, model, h, forced)
+        });
+
+        (self.trial, self.stats) = (trial, stats);
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        Some(self.stats)
+    }
+
+    fn reset(&mut self) {
+        self.b.clear();
+        self.position_error.clear();
+        self.velocity_error.clear();
+        self.scale = 0.0;
+    }
+
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.option_f64(self.trial);
+        encoder.f64(self.scale);
+        self.stats.save(encoder);
+
+        // Empty after a reset, rather than sized to the bodies
+        encoder.usize(self.b.len());
+        for i in 0..self.b.len() {
+            for b in self.b[i] {
+                encoder.vec3(b);
+            }
+            encoder.vec3(self.position_error[i]);
+            encoder.vec3(self.velocity_error[i]);
+        }
+    }
+
+    fn load(&mut self, decoder: &mut Decoder, len: usize) -> Result<(), CheckpointError> {
+        self.trial = decoder.option_f64()?;
+        self.scale = decoder.f64()?;
+        self.stats = Stats::load(decoder)?;
+
+        let carried = decoder.count(9 * VEC3)?;
+        if carried != 0 && carried != len {
+            return Err(CheckpointError::Invalid("integrator state"));
+        }
+
+        self.resize(carried);
+        for i in 0..carried {
+            for b in self.b[i].iter_mut() {
+                *b = decoder.vec3()?;
+            }
+            self.position_error[i] = decoder.vec3()?;
+            self.velocity_error[i] = decoder.vec3()?;
+        }
+
+        Ok(())
+    }
+}
+
+fn kahan(sum: &mut DVec3, error: &mut DVec3, value: DVec3) {
+    let y = value - *error;
+    let t = *sum + y;
+    *error = (t - *sum) - y;
+    *sum = t;
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |c, i| c * (n - i) as f64 / (i + 1) as f64)
+}
@@ -0,0 +1,14 @@
+use super::{Integrator, Model, State};
+
+// Velocity Verlet in kick-drift-kick form, second order and symplectic
+pub struct Leapfrog;
+
+impl Integrator for Leapfrog {
+    fn step(&mut self, state: &mut State, model: &dyn Model, step: f64) {
+        let half_step = step / 2.0;
+
+        state.kick(&model.accelerations(state), half_step);
+        state.drift(step);
+        state.kick(&model.accelerations(state), half_step);
+    }
+}
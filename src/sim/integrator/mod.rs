@@ -0,0 +1,219 @@
+use super::checkpoint::{CheckpointError, Decoder, Encoder};
+use glam::f64::DVec3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntegratorType {
+    Euler,
+    SemiImplicitEuler,
+    Leapfrog,
+    Rk4,
+    Yoshida,
+    DormandPrince { atol: f64, rtol: f64 },
+    Ias15 { epsilon: f64 },
+    WisdomHolman,
+}
+
+impl IntegratorType {
+    pub fn integrator(&self) -> Box<dyn Integrator> {
+        match self {
+            IntegratorType::Euler => Box::new(euler::Euler),
+            IntegratorType::SemiImplicitEuler => Box::new(euler::SemiImplicitEuler),
+            IntegratorType::Leapfrog => Box::new(leapfrog::Leapfrog),
+            IntegratorType::Rk4 => Box::new(rk4::Rk4),
+            IntegratorType::Yoshida => Box::new(yoshida::Yoshida),
+            IntegratorType::DormandPrince { atol, rtol } => {
+                Box::new(dopri::DormandPrince::new(*atol, *rtol))
+            }
+            IntegratorType::Ias15 { epsilon } => Box::new(ias15::Ias15::new(*epsilon)),
+            IntegratorType::WisdomHolman => Box::new(wisdom_holman::WisdomHolman),
+        }
+    }
+
+    // Whether a step of -h exactly undoes a step of h, up to round-off, under forces depending on
+    // position alone. Any integrator can step backwards, but only these retrace their own
+    // trajectory. See `System::is_time_reversible` for the system as a whole.
+    pub fn is_time_reversible(&self) -> bool {
+        matches!(
+            self,
+            IntegratorType::Leapfrog | IntegratorType::Yoshida | IntegratorType::WisdomHolman
+        )
+    }
+}
+
+// Phase space of every body, indexed in the same order as `System::bodies`
+#[derive(Clone)]
+pub struct State {
+    pub positions: Vec<DVec3>,
+    pub velocities: Vec<DVec3>,
+}
+
+impl State {
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn is_finite(&self) -> bool {
+        self.positions
+            .iter()
+            .chain(&self.velocities)
+            .all(|x| x.is_finite())
+    }
+
+    fn drift(&mut self, step: f64) {
+        for (r, v) in self.positions.iter_mut().zip(&self.velocities) {
+            *r += step * *v;
+        }
+    }
+
+    fn kick(&mut self, accelerations: &[DVec3], step: f64) {
+        for (v, a) in self.velocities.iter_mut().zip(accelerations) {
+            *v += step * *a;
+        }
+    }
+
+    // Velocities and accelerations packed into a state, so Runge-Kutta stages can treat them
+    // like any other state
+    fn derivative(&self, model: &dyn Model) -> State {
+        State {
+            positions: self.velocities.clone(),
+            velocities: model.accelerations(self),
+        }
+    }
+
+    // self + step * Σ weight_j k_j
+    fn stage(&self, ks: &[&State], weights: &[f64], step: f64) -> State {
+        let mut stage = self.clone();
+
+        for (k, weight) in ks.iter().zip(weights) {
+            if *weight == 0.0 {
+                continue;
+            }
+
+            for i in 0..stage.len() {
+                stage.positions[i] += step * weight * k.positions[i];
+                stage.velocities[i] += step * weight * k.velocities[i];
+            }
+        }
+
+        stage
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Stats {
+    pub accepted: usize,
+    pub rejected: usize,
+}
+
+impl Stats {
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.usize(self.accepted);
+        encoder.usize(self.rejected);
+    }
+
+    fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        Ok(Self {
+            accepted: decoder.usize()?,
+            rejected: decoder.usize()?,
+        })
+    }
+}
+
+// What came of an attempted sub-step, with the sub-step to try next
+pub(super) enum Attempt {
+    Accepted { next: f64 },
+    Rejected { next: f64 },
+    // Accepted, but the state is no longer finite, so there's nothing left to integrate
+    Abandoned,
+}
+
+// Covers `step` in sub-steps chosen by an adaptive integrator. `attempt` tries a sub-step, and
+// must accept it when forced to, once it can no longer be told apart from rounding. `trial`
+// carries the sub-step to try first over between calls.
+pub(super) fn substeps(
+    step: f64,
+    trial: &mut Option<f64>,
+    stats: &mut Stats,
+    mut attempt: impl FnMut(f64, bool) -> Attempt,
+) {
+    let mut remaining = step;
+
+    while remaining != 0.0 {
+        // Carry the magnitude of the last accepted sub-step over, pointing it in the direction of
+        // integration
+        let mut h = trial.unwrap_or(remaining).abs().copysign(remaining);
+        let last = h.abs() >= remaining.abs();
+        if last {
+            h = remaining;
+        }
+
+        let forced = h.abs() <= f64::EPSILON * step.abs();
+
+        match attempt(h, forced) {
+            Attempt::Accepted { next } => {
+                stats.accepted += 1;
+                remaining = if last { 0.0 } else { remaining - h };
+
+                // A short final sub-step to land on the requested time says nothing about what
+                // the next sub-step can be, unless it has to be smaller still
+                if !last || trial.is_none() || next.abs() < h.abs() {
+                    *trial = Some(next);
+                }
+            }
+            Attempt::Rejected { next } => {
+                stats.rejected += 1;
+                *trial = Some(next);
+            }
+            Attempt::Abandoned => {
+                stats.accepted += 1;
+                *trial = None;
+                return;
+            }
+        }
+    }
+}
+
+// The forces an integrator is advancing the state under
+pub trait Model {
+    fn masses(&self) -> &[f64];
+
+    fn gravitational_constant(&self) -> f64;
+
+    fn softening(&self) -> f64;
+
+    fn accelerations(&self, state: &State) -> Vec<DVec3>;
+}
+
+pub trait Integrator {
+    fn step(&mut self, state: &mut State, model: &dyn Model, step: f64);
+
+    // Accepted and rejected internal steps, for integrators that choose their own step size
+    fn stats(&self) -> Option<Stats> {
+        None
+    }
+
+    // Drops anything carried over between steps about individual bodies, called whenever bodies
+    // are added or removed
+    fn reset(&mut self) {}
+
+    // Writes anything carried over between steps to a checkpoint, so a restored run continues
+    // exactly as the original would have
+    fn save(&self, _encoder: &mut Encoder) {}
+
+    // Reads back what `save` wrote, for `len` bodies
+    fn load(&mut self, _decoder: &mut Decoder, _len: usize) -> Result<(), CheckpointError> {
+        Ok(())
+    }
+}
+
+pub mod dopri;
+pub mod euler;
+pub mod ias15;
+pub mod leapfrog;
+pub mod rk4;
+pub mod wisdom_holman;
+pub mod yoshida;
@@ -0,0 +1,20 @@
+use super::{Integrator, Model, State};
+
+// Classical 4th order Runge-Kutta over the whole system. Every stage advances all bodies together,
+// so each derivative is evaluated on a consistent state.
+pub struct Rk4;
+
+impl Integrator for Rk4 {
+    fn step(&mut self, state: &mut State, model: &dyn Model, step: f64) {
+        let k1 = state.derivative(model);
+        let k2 = state.stage(&[&k1], &[0.5], step).derivative(model);
+        let k3 = state.stage(&[&k2], &[0.5], step).derivative(model);
+        let k4 = state.stage(&[&k3], &[1.0], step).derivative(model);
+
+        *state = state.stage(
+            &[&k1, &k2, &k3, &k4],
+            &[1.0 / 6.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 6.0],
+            step,
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use super::{Integrator, Model, State};
+use crate::sim::kepler;
+use glam::f64::DVec3;
+
+// Wisdom-Holman mapping in democratic heliocentric coordinates (Duncan, Levison & Lee 1998).
+//
+// The Hamiltonian is split into Kepler motion about the dominant body, the interactions between
+// the other bodies and a jump from the momentum of the dominant body. Each is solved exactly in
+// turn, so the step only has to resolve the interactions rather than the orbits themselves.
+pub struct WisdomHolman;
+
+// Heliocentric positions and barycentric velocities of every body, relative to the dominant one
+struct Heliocentric {
+    central: usize,
+    center_of_mass: DVec3,
+    momentum: DVec3,
+    positions: Vec<DVec3>,
+    velocities: Vec<DVec3>,
+}
+
+impl Heliocentric {
+    fn new(state: &State, masses: &[f64]) -> Self {
+        let central = masses
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(i, _)| i);
+
+        let total = masses.iter().sum::<f64>();
+        let center_of_mass = weighted_sum(&state.positions, masses) / total;
+        let momentum = weighted_sum(&state.velocities, masses);
+        let velocity = momentum / total;
+
+        Self {
+            central,
+            center_of_mass,
+            momentum,
+            positions: state
+                .positions
+                .iter()
+                .map(|r| *r - state.positions[central])
+                .collect(),
+            velocities: state.velocities.iter().map(|v| *v - velocity).collect(),
+        }
+    }
+
+    fn state(&self, masses: &[f64]) -> State {
+        let total = masses.iter().sum::<f64>();
+        let central_mass = masses[self.central];
+
+        let mut offset = weighted_sum(&self.positions, masses);
+        let mut momentum = weighted_sum(&self.velocities, masses);
+        offset -= central_mass * self.positions[self.central];
+        momentum -= central_mass * self.velocities[self.central];
+
+        let central_position = self.center_of_mass - offset / total;
+        let velocity = self.momentum / total;
+        let central_velocity = velocity - momentum / central_mass;
+
+        State {
+            positions: self
+                .positions
+                .iter()
+                .enumerate()
+                .map(|(i, r)| {
+                    if i == self.central {
+                        central_position
+                    } else {
+                        *r + central_position
+                    }
+                })
+                .collect(),
+            velocities: self
+                .velocities
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    if i == self.central {
+                        central_velocity
+                    } else {
+                        *v + velocity
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    // Mutual interactions of every body other than the dominant one
+    fn kick(&mut self, model: &dyn Model, masses: &[f64], step: f64) {
+        let state = State {
+            positions: self.positions.clone(),
+            velocities: self.velocities.clone(),
+        };
+        let mu = model.gravitational_constant() * masses[self.central];
+        let softening2 = model.softening().powi(2);
+
+        for (i, a) in model.accelerations(&state).iter().enumerate() {
+            if i == self.central {
+                continue;
+            }
+
+            // Pull of the dominant body, as the model sees it
+            let r = self.positions[i];
+            let r2 = r.length_squared() + softening2;
+            let kepler = -mu / (r2 * r2.sqrt()) * r;
+            self.velocities[i] += step * (*a - kepler);
+        }
+    }
+
+    // Momentum of the dominant body, from the barycentric momenta of the others
+    fn jump(&mut self, masses: &[f64], step: f64) {
+        let mut momentum = weighted_sum(&self.velocities, masses);
+        momentum -= masses[self.central] * self.velocities[self.central];
+
+        let shift = step / masses[self.central] * momentum;
+        for (i, r) in self.positions.iter_mut().enumerate() {
+            if i != self.central {
+                *r += shift;
+            }
+        }
+    }
+
+    fn drift(&mut self, model: &dyn Model, masses: &[f64], step: f64) {
+        let mu = model.gravitational_constant() * masses[self.central];
+
+        for i in 0..self.positions.len() {
+            if i != self.central {
+                (self.positions[i], self.velocities[i]) =
+                    kepler::propagate(self.positions[i], self.velocities[i], mu, step);
+            }
+        }
+
+        self.center_of_mass += step / masses.iter().sum::<f64>() * self.momentum;
+    }
+}
+
+impl Integrator for WisdomHolman {
+    fn step(&mut self, state: &mut State, model: &dyn Model, step: f64) {
+        let masses = model.masses();
+        let half_step = step / 2.0;
+
+        // A lone body, or one among test particles alone, has nothing to orbit, so there's no
+        // Kepler motion to split off, only whatever other forces act on it
+        if state.len() < 2 || masses.iter().all(|&m| m == 0.0) {
+            state.kick(&model.accelerations(state), half_step);
+            state.drift(step);
+            state.kick(&model.accelerations(state), half_step);
+            return;
+        }
+
+        let mut heliocentric = Heliocentric::new(state, masses);
+
+        heliocentric.kick(model, masses, half_step);
+        heliocentric.jump(masses, half_step);
+        heliocentric.drift(model, masses, step);
+        heliocentric.jump(masses, half_step);
+        heliocentric.kick(model, masses, half_step);
+
+        *state = heliocentric.state(masses);
+    }
+}
+
+fn weighted_sum(values: &[DVec3], weights: &[f64]) -> DVec3 {
+    values
+        .iter()
+        .zip(weights)
+        .fold(DVec3::ZERO, |sum, (x, m)| sum + *m * *x)
+}
@@ -0,0 +1,22 @@
+use super::{Integrator, Model, State};
+
+// Yoshida's 4th order symplectic integrator, three leapfrog steps with weights
+// w1, w0, w1 where w1 = 1 / (2 - 2^(1/3)) and w0 = 1 - 2 * w1
+const W1: f64 = 1.351_207_191_959_657_8;
+const W0: f64 = -1.702_414_383_919_315_3;
+
+const C: [f64; 4] = [W1 / 2.0, (W0 + W1) / 2.0, (W0 + W1) / 2.0, W1 / 2.0];
+const D: [f64; 3] = [W1, W0, W1];
+
+pub struct Yoshida;
+
+impl Integrator for Yoshida {
+    fn step(&mut self, state: &mut State, model: &dyn Model, step: f64) {
+        for (c, d) in C.iter().zip(D) {
+            state.drift(c * step);
+            state.kick(&model.accelerations(state), d * step);
+        }
+
+        state.drift(C[3] * step);
+    }
+}
@@ -0,0 +1,147 @@
+use glam::f64::DVec3;
+
+// Advances a two-body orbit by `dt` exactly, up to round-off, from the position and velocity of
+// one body relative to the other and μ = G (m₁ + m₂). Kepler's equation is solved in universal
+// variables with Laguerre-Conway iterations, so the orbit can be elliptic, parabolic or
+// hyperbolic, and `dt` negative.
+pub fn propagate(position: DVec3, velocity: DVec3, mu: f64, dt: f64) -> (DVec3, DVec3) {
+    const MAX_ITERATIONS: usize = 50;
+    const N: f64 = 5.0;
+
+    let r0 = position.length();
+    let sqrt_mu = mu.sqrt();
+    let sigma = position.dot(velocity) / sqrt_mu;
+    // Reciprocal of the semi-major axis, negative for hyperbolic orbits
+    let alpha = 2.0 / r0 - velocity.length_squared() / mu;
+
+    // Universal Kepler's equation, as the time of flight less `dt` (times √μ), with its first two
+    // derivatives in χ
+    let kepler = |chi: f64| {
+        let chi2 = chi * chi;
+        let z = alpha * chi2;
+        let (c, s) = stumpff(z);
+
+        (
+            sigma * chi2 * c + (1.0 - alpha * r0) * chi2 * chi * s + r0 * chi - sqrt_mu * dt,
+            sigma * chi * (1.0 - z * s) + (1.0 - alpha * r0) * chi2 * c + r0,
+            sigma * (1.0 - z * c) + (1.0 - alpha * r0) * chi * (1.0 - z * s),
+        )
+    };
+
+    let linear = sqrt_mu * dt / r0;
+    let mut chi = if alpha > 0.0 {
+        sqrt_mu * dt * alpha
+    } else if alpha < 0.0 {
+        // Logarithmic in `dt`, as χ is for hyperbolic orbits, rather than linear, which overflows
+        // cosh and sinh long before the iterations can bring it back (Vallado, algorithm 8)
+        let a = 1.0 / alpha;
+        let sign = dt.signum();
+        let guess = sign
+            * (-a).sqrt()
+            * (-2.0 * mu * alpha * dt
+                / (position.dot(velocity) + sign * (-mu * a).sqrt() * (1.0 - r0 * alpha)))
+                .ln();
+        if guess.is_finite() {
+            guess
+        } else {
+            linear
+        }
+    } else {
+        linear
+    };
+
+    let mut converged = false;
+    for _ in 0..MAX_ITERATIONS {
+        let (f, df, ddf) = kepler(chi);
+
+        let root = ((N - 1.0).powi(2) * df * df - N * (N - 1.0) * f * ddf)
+            .abs()
+            .sqrt();
+        let delta = N * f / (df + root.copysign(df));
+
+        chi -= delta;
+        if delta.abs() <= f64::EPSILON * chi.abs().max(1.0) {
+            converged = true;
+            break;
+        }
+    }
+
+    // The time of flight only ever grows with χ, at a rate r, so bisection can't fail where the
+    // iterations did
+    if !converged || !chi.is_finite() {
+        chi = bisect(|chi| kepler(chi).0, linear);
+    }
+
+    let chi2 = chi * chi;
+    let z = alpha * chi2;
+    let (c, s) = stumpff(z);
+
+    let f = 1.0 - chi2 / r0 * c;
+    let g = dt - chi2 * chi / sqrt_mu * s;
+    let new_position = f * position + g * velocity;
+
+    let r = new_position.length();
+    let df = sqrt_mu / (r * r0) * chi * (z * s - 1.0);
+    let dg = 1.0 - chi2 / r * c;
+
+    (new_position, df * position + dg * velocity)
+}
+
+// Root of `f`, increasing through zero, and negative at zero in the direction of `guess`
+fn bisect(f: impl Fn(f64) -> f64, guess: f64) -> f64 {
+    let sign = guess.signum();
+
+    // Out past the root, where overflowing to infinity or NaN counts as past it too
+    let mut high = guess;
+    while sign * f(high) < 0.0 {
+        high *= 2.0;
+    }
+
+    let mut low = 0.0;
+    for _ in 0..2048 {
+        let mid = (low + high) / 2.0;
+        if mid == low || mid == high {
+            break;
+        }
+
+        if sign * f(mid) < 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    (low + high) / 2.0
+}
+
+// Stumpff functions c2(z) and c3(z)
+fn stumpff(z: f64) -> (f64, f64) {
+    if z.abs() < 1e-2 {
+        // Series, the closed forms cancel catastrophically around zero
+        let mut c = 0.0;
+        let mut s = 0.0;
+        let mut term = 1.0;
+        for k in 0..8 {
+            c += term / factorial(2 * k + 2);
+            s += term / factorial(2 * k + 3);
+            term *= -z;
+        }
+        (c, s)
+    } else if z > 0.0 {
+        let sqrt_z = z.sqrt();
+        (
+            (1.0 - sqrt_z.cos()) / z,
+            (sqrt_z - sqrt_z.sin()) / (z * sqrt_z),
+        )
+    } else {
+        let sqrt_z = (-z).sqrt();
+        (
+            (sqrt_z.cosh() - 1.0) / -z,
+            (sqrt_z.sinh() - sqrt_z) / (-z * sqrt_z),
+        )
+    }
+}
+
+fn factorial(n: usize) -> f64 {
+    (1..=n).fold(1.0, |f, k| f * k as f64)
+}
@@ -1,39 +1,275 @@
-use glam::DVec3;
+use std::{f64::consts::FRAC_PI_2, fs, path::Path};
 
-use self::{body::BodyBuilder, system::System};
+use self::{
+    body::{Body, BodyBuilder},
+    checkpoint::{CheckpointError, Decoder, Encoder},
+    collision::CollisionPolicy,
+    cr3bp::Cr3bp,
+    crossing::EventFunction,
+    encounter::EncounterRadius,
+    error::SimError,
+    escape::EscapeCriterion,
+    event::Event,
+    force::{Force, Targets},
+    frame::Frame,
+    gravity::GravityType,
+    integrator::IntegratorType,
+    orbit::Orbit,
+    system::{Settings, System},
+    units::Units,
+};
 
 pub struct Sim {
     system: System,
 }
 
 impl Sim {
-    // TODO: Init from config file
-    pub fn new() -> Self {
-        let mut system = System::new();
+    pub fn new(integrator: IntegratorType) -> Self {
+        SimBuilder::new(integrator).build()
+    }
 
-        // 1 solar mass (sun)
-        let mut body = BodyBuilder::new(1.0)
-            .with_position(DVec3::new(0.0, 0.0, 0.0))
-            .build();
-        system.insert(body);
-        // Earth
-        body = BodyBuilder::new(3e-6)
-            .with_position(DVec3::new(0.0, -0.98329, 0.0))
-            .with_velocity(DVec3::new(6.38966, 0.0, 0.0))
-            .build();
-        system.insert(body);
+    // Negative `dt` runs the sim backwards, retracing it exactly with a time-reversible
+    // integrator
+    pub fn step(&mut self, dt: f64) -> Result<(), SimError> {
+        self.system.step(dt)
+    }
+
+    // Measures drift in the diagnostics from the current state, for after deliberate changes like
+    // removing a body
+    pub fn reset_diagnostics(&mut self) {
+        self.system.reset_diagnostics();
+    }
+
+    // Takes the body with the given id out of the sim
+    pub fn remove(&mut self, id: usize) -> Option<Body> {
+        self.system.remove(id)
+    }
+
+    // The full state, to resume from with `restore`. Forces and event functions aren't included,
+    // so re-attach them to the restored sim with `add_force` and `add_event_function`.
+    pub fn checkpoint(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+        self.system.save(&mut encoder);
+        encoder.finish()
+    }
+
+    // Resumes from a checkpoint, stepping on bit for bit as the original sim would have
+    pub fn restore(bytes: &[u8]) -> Result<Self, CheckpointError> {
+        let mut decoder = Decoder::new(bytes)?;
+        let system = System::load(&mut decoder)?;
+        decoder.finish()?;
+
+        Ok(Sim { system })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+        Ok(fs::write(path, self.checkpoint())?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        Self::restore(&fs::read(path)?)
+    }
 
-        Self { system }
+    pub fn add_force(&mut self, force: impl Force + 'static, targets: Targets) {
+        self.system.add_force(Box::new(force), targets);
     }
 
-    pub fn step(&mut self, dt: f64) {
-        self.system.step(dt);
+    pub fn add_event_function(&mut self, function: EventFunction) {
+        self.system.add_event_function(function);
     }
 
     pub fn system(&self) -> &System {
         &self.system
     }
+
+    // Takes the events logged since the last call
+    pub fn drain_events(&mut self) -> Vec<Event> {
+        self.system.drain_events()
+    }
+}
+
+impl Default for Sim {
+    fn default() -> Self {
+        Self::new(IntegratorType::Rk4)
+    }
+}
+
+pub struct SimBuilder {
+    integrator: IntegratorType,
+    gravity: GravityType,
+    forces: Vec<(Box<dyn Force>, Targets)>,
+    functions: Vec<EventFunction>,
+    settings: Settings,
+    frame: Frame,
+    bodies: Option<Vec<Body>>,
+}
+
+impl SimBuilder {
+    pub fn new(integrator: IntegratorType) -> Self {
+        Self {
+            integrator,
+            gravity: GravityType::Direct,
+            forces: vec![],
+            functions: vec![],
+            settings: Settings {
+                units: Units::Astronomical,
+                softening: 0.0,
+                collisions: CollisionPolicy::Merge,
+                encounters: None,
+                escapes: None,
+                relativity: false,
+                restricted: None,
+            },
+            frame: Frame::AsGiven,
+            bodies: None,
+        }
+    }
+
+    pub fn with_gravity(mut self, gravity: GravityType) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.settings.units = units;
+        self
+    }
+
+    // Plummer softening length, in the units of the sim
+    pub fn with_softening(mut self, softening: f64) -> Self {
+        self.settings.softening = softening;
+        self
+    }
+
+    // How bodies given a radius collide
+    pub fn with_collisions(mut self, collisions: CollisionPolicy) -> Self {
+        self.settings.collisions = collisions;
+        self
+    }
+
+    // Logs close encounters between bodies passing within `radius` of each other
+    pub fn with_encounters(mut self, radius: EncounterRadius) -> Self {
+        self.settings.encounters = Some(radius);
+        self
+    }
+
+    // Removes bodies leaving the system by `criterion`
+    pub fn with_escapes(mut self, criterion: EscapeCriterion) -> Self {
+        self.settings.escapes = Some(criterion);
+        self
+    }
+
+    // Adds a force on top of gravity, acting on `targets`
+    pub fn with_force(mut self, force: impl Force + 'static, targets: Targets) -> Self {
+        self.forces.push((Box::new(force), targets));
+        self
+    }
+
+    // Logs each zero crossing of `function` as an event
+    pub fn with_event_function(mut self, function: EventFunction) -> Self {
+        self.functions.push(function);
+        self
+    }
+
+    // Adds the first post-Newtonian correction of general relativity about the most massive body
+    pub fn with_relativity(mut self) -> Self {
+        self.settings.relativity = true;
+        self
+    }
+
+    // Circular restricted three-body mode. Every body is integrated as a test particle in the
+    // frame co-rotating with the primaries of `problem`, in its normalized units rather than those
+    // of the sim. Panics on build with Wisdom-Holman, whose Kepler motion about a dominant body
+    // doesn't apply in the rotating frame.
+    pub fn with_restricted(mut self, problem: Cr3bp) -> Self {
+        self.settings.restricted = Some(problem);
+        self
+    }
+
+    // Frame to shift the initial bodies into. Panics on build if centered on a body that isn't
+    // given.
+    pub fn with_frame(mut self, frame: Frame) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    // Bodies to start with, in the units of the sim, in place of the default sun and earth
+    pub fn with_bodies(mut self, bodies: Vec<Body>) -> Self {
+        self.bodies = Some(bodies);
+        self
+    }
+
+    // TODO: Init from config file
+    pub fn build(self) -> Sim {
+        assert!(
+            self.settings.restricted.is_none() || self.integrator != IntegratorType::WisdomHolman,
+            "Wisdom-Holman can't integrate the restricted three-body problem"
+        );
+
+        let mut system = System::new(
+            self.integrator,
+            self.gravity,
+            self.forces,
+            self.functions,
+            self.settings,
+        );
+
+        if let Some(bodies) = self.bodies {
+            for body in bodies {
+                system.insert(body);
+            }
+            system.shift_to(self.frame);
+            system.reset_diagnostics();
+
+            return Sim { system };
+        }
+
+        // Written in AU, solar masses and years
+        let au = Units::Astronomical;
+        let units = self.settings.units;
+
+        // 1 solar mass (sun)
+        let sun = BodyBuilder::new(au.convert_mass(1.0, units)).build();
+        system.insert(sun);
+        // Earth, at perihelion
+        let earth = BodyBuilder::new(au.convert_mass(3e-6, units))
+            .with_orbit(
+                &sun,
+                Orbit::new(
+                    au.convert_length(1.000_002_61, units),
+                    0.016_711_23,
+                    0.0,
+                    0.0,
+                    -FRAC_PI_2,
+                    0.0,
+                ),
+                units,
+            )
+            .build();
+        system.insert(earth);
+        system.shift_to(self.frame);
+        system.reset_diagnostics();
+
+        Sim { system }
+    }
 }
 
 pub mod body;
+pub mod checkpoint;
+pub mod collision;
+pub mod cr3bp;
+pub mod crossing;
+pub mod diagnostics;
+pub mod encounter;
+pub mod error;
+pub mod escape;
+pub mod event;
+pub mod force;
+pub mod frame;
+pub mod gravity;
+pub mod integrator;
+pub mod kepler;
+pub mod orbit;
+mod relativity;
 pub mod system;
+pub mod units;
@@ -0,0 +1,259 @@
+use glam::f64::{DMat3, DVec3};
+use std::f64::consts::{PI, TAU};
+
+// Where along its orbit a body is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Anomaly {
+    Mean(f64),
+    True(f64),
+    // Ω + ω + M
+    MeanLongitude(f64),
+}
+
+// Keplerian elements of an orbit about a primary, with angles in radians. Hyperbolic orbits
+// (e > 1) take a negative semi-major axis, though its sign is ignored. Parabolic orbits (e = 1)
+// have no finite semi-major axis, so take their periapsis distance in its place, and a mean
+// anomaly M = D + D³/3 with D = tan(ν/2) (Barker's equation).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orbit {
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    // Longitude of the ascending node Ω
+    pub node: f64,
+    // Argument of periapsis ω
+    pub periapsis: f64,
+    pub anomaly: Anomaly,
+}
+
+impl Orbit {
+    pub fn new(
+        semi_major_axis: f64,
+        eccentricity: f64,
+        inclination: f64,
+        node: f64,
+        periapsis: f64,
+        mean_anomaly: f64,
+    ) -> Self {
+        Self {
+            semi_major_axis,
+            eccentricity,
+            inclination,
+            node,
+            periapsis,
+            anomaly: Anomaly::Mean(mean_anomaly),
+        }
+    }
+
+    pub fn with_true_anomaly(mut self, true_anomaly: f64) -> Self {
+        self.anomaly = Anomaly::True(true_anomaly);
+        self
+    }
+
+    pub fn with_mean_longitude(mut self, mean_longitude: f64) -> Self {
+        self.anomaly = Anomaly::MeanLongitude(mean_longitude);
+        self
+    }
+
+    // Semi-latus rectum
+    fn semi_latus_rectum(&self) -> f64 {
+        let (a, e) = (self.semi_major_axis.abs(), self.eccentricity);
+        if e == 1.0 {
+            2.0 * a
+        } else {
+            a * (1.0 - e * e).abs()
+        }
+    }
+
+    pub fn true_anomaly(&self) -> f64 {
+        let e = self.eccentricity;
+        let mean = match self.anomaly {
+            Anomaly::True(true_anomaly) => return true_anomaly,
+            Anomaly::Mean(mean) => mean,
+            Anomaly::MeanLongitude(longitude) => longitude - self.node - self.periapsis,
+        };
+
+        if e < 1.0 {
+            let eccentric = eccentric_anomaly(mean, e);
+            2.0 * ((1.0 + e).sqrt() * (eccentric / 2.0).sin())
+                .atan2((1.0 - e).sqrt() * (eccentric / 2.0).cos())
+        } else if e > 1.0 {
+            let hyperbolic = hyperbolic_anomaly(mean, e);
+            2.0 * (((e + 1.0) / (e - 1.0)).sqrt() * (hyperbolic / 2.0).tanh()).atan()
+        } else {
+            // Cardano's solution of D³ + 3D - 3M = 0
+            let y = 1.5 * mean;
+            let s = (y * y + 1.0).sqrt();
+            2.0 * ((y + s).cbrt() + (y - s).cbrt()).atan()
+        }
+    }
+
+    // Position and velocity relative to the primary, for a standard gravitational parameter
+    // μ = G (m₁ + m₂)
+    pub fn state(&self, mu: f64) -> (DVec3, DVec3) {
+        let (e, p) = (self.eccentricity, self.semi_latus_rectum());
+        let nu = self.true_anomaly();
+
+        let r = p / (1.0 + e * nu.cos());
+        let position = r * DVec3::new(nu.cos(), nu.sin(), 0.0);
+        let velocity = (mu / p).sqrt() * DVec3::new(-nu.sin(), e + nu.cos(), 0.0);
+
+        let rotation = self.rotation();
+        (rotation * position, rotation * velocity)
+    }
+
+    // From the perifocal frame to the reference frame
+    fn rotation(&self) -> DMat3 {
+        DMat3::from_rotation_z(self.node)
+            * DMat3::from_rotation_x(self.inclination)
+            * DMat3::from_rotation_z(self.periapsis)
+    }
+}
+
+// What an orbit is taken to be around, when working out its elements
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Primary {
+    // Body with the given id
+    Body(usize),
+    // Barycenter of every body closer to the most massive body, including it
+    Interior,
+}
+
+// Eccentricities this close to 1 are taken as parabolic
+const PARABOLIC: f64 = 1e-12;
+// Eccentricities and inclinations this small leave periapsis and the node undefined
+const DEGENERATE: f64 = 1e-12;
+
+// Osculating elements of an orbit, with angles in radians, following the conventions of `Orbit`.
+// For circular orbits ω = 0 and the anomalies are measured from the node, and for equatorial ones
+// Ω = 0 and the node is taken to be along x.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Elements {
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub node: f64,
+    pub periapsis: f64,
+    pub true_anomaly: f64,
+    // Eccentric, hyperbolic or parabolic (D = tan(ν/2)) anomaly
+    pub eccentric_anomaly: f64,
+    pub mean_anomaly: f64,
+    // None for unbound orbits
+    pub period: Option<f64>,
+}
+
+impl Elements {
+    // From position and velocity relative to the primary, for a standard gravitational parameter
+    // μ = G (m₁ + m₂)
+    pub fn new(position: DVec3, velocity: DVec3, mu: f64) -> Self {
+        let r = position.length();
+        let h = position.cross(velocity);
+        let normal = h.normalize_or_zero();
+        let e_vector = ((velocity.length_squared() - mu / r) * position
+            - position.dot(velocity) * velocity)
+            / mu;
+
+        let mut e = e_vector.length();
+        if (e - 1.0).abs() < PARABOLIC {
+            e = 1.0;
+        }
+        let p = h.length_squared() / mu;
+        let energy = velocity.length_squared() / 2.0 - mu / r;
+
+        let inclination = (normal.z).clamp(-1.0, 1.0).acos();
+        let n = DVec3::new(-h.y, h.x, 0.0);
+        let (node, n) = if n.length() > DEGENERATE * h.length() {
+            (n.y.atan2(n.x).rem_euclid(TAU), n.normalize())
+        } else {
+            (0.0, DVec3::X)
+        };
+
+        // Angle from `from` to `to`, about the orbit normal
+        let angle = |from: DVec3, to: DVec3| normal.dot(from.cross(to)).atan2(from.dot(to));
+
+        let (periapsis, true_anomaly) = if e > DEGENERATE {
+            let periapsis = angle(n, e_vector).rem_euclid(TAU);
+            (periapsis, angle(e_vector, position))
+        } else {
+            (0.0, angle(n, position))
+        };
+
+        let (semi_major_axis, eccentric_anomaly, mean_anomaly) = if e < 1.0 {
+            let eccentric =
+                ((1.0 - e * e).sqrt() * true_anomaly.sin()).atan2(e + true_anomaly.cos());
+            (
+                -mu / (2.0 * energy),
+                eccentric,
+                eccentric - e * eccentric.sin(),
+            )
+        } else if e > 1.0 {
+            let hyperbolic =
+                2.0 * (((e - 1.0) / (e + 1.0)).sqrt() * (true_anomaly / 2.0).tan()).atanh();
+            (
+                -mu / (2.0 * energy),
+                hyperbolic,
+                e * hyperbolic.sinh() - hyperbolic,
+            )
+        } else {
+            let d = (true_anomaly / 2.0).tan();
+            (p / 2.0, d, d + d.powi(3) / 3.0)
+        };
+
+        let period = (e < 1.0).then(|| TAU * (semi_major_axis.powi(3) / mu).sqrt());
+
+        Self {
+            semi_major_axis,
+            eccentricity: e,
+            inclination,
+            node,
+            periapsis,
+            true_anomaly,
+            eccentric_anomaly,
+            mean_anomaly,
+            period,
+        }
+    }
+
+    pub fn orbit(&self) -> Orbit {
+        Orbit::new(
+            self.semi_major_axis,
+            self.eccentricity,
+            self.inclination,
+            self.node,
+            self.periapsis,
+            self.mean_anomaly,
+        )
+        .with_true_anomaly(self.true_anomaly)
+    }
+}
+
+// Solves Kepler's equation M = E - e sin E
+fn eccentric_anomaly(mean: f64, e: f64) -> f64 {
+    let mean = (mean + PI).rem_euclid(TAU) - PI;
+    let mut eccentric = if e < 0.8 { mean } else { PI.copysign(mean) };
+
+    for _ in 0..64 {
+        let delta = (eccentric - e * eccentric.sin() - mean) / (1.0 - e * eccentric.cos());
+        eccentric -= delta;
+        if delta.abs() <= 1e-15 * eccentric.abs().max(1.0) {
+            break;
+        }
+    }
+
+    eccentric
+}
+
+// Solves M = e sinh H - H
+fn hyperbolic_anomaly(mean: f64, e: f64) -> f64 {
+    let mut hyperbolic = (2.0 * mean.abs() / e + 1.8).ln().copysign(mean);
+
+    for _ in 0..64 {
+        let delta = (e * hyperbolic.sinh() - hyperbolic - mean) / (e * hyperbolic.cosh() - 1.0);
+        hyperbolic -= delta;
+        if delta.abs() <= 1e-15 * hyperbolic.abs().max(1.0) {
+            break;
+        }
+    }
+
+    hyperbolic
+}
@@ -0,0 +1,39 @@
+use super::integrator::State;
+use glam::f64::DVec3;
+
+// First post-Newtonian correction to the orbit of every body about the most massive one, treated
+// as a Schwarzschild mass:
+//   a = μ / (c² r³) ((4μ/r - v²) r + 4 (r·v) v)
+// with r and v relative to the most massive body, and μ = G (m₁ + m₂). Periapsis precesses by
+// 6πμ / (c² a (1 - e²)) per orbit, as in general relativity. The most massive body recoils so
+// momentum is conserved.
+pub(super) fn correct(
+    accelerations: &mut [DVec3],
+    state: &State,
+    masses: &[f64],
+    g: f64,
+    speed_of_light: f64,
+) {
+    let Some(central) = (0..masses.len()).max_by(|&a, &b| masses[a].total_cmp(&masses[b])) else {
+        return;
+    };
+    let c2 = speed_of_light * speed_of_light;
+
+    for i in 0..masses.len() {
+        let mass = masses[central] + masses[i];
+        if i == central || mass <= 0.0 {
+            continue;
+        }
+
+        let r = state.positions[i] - state.positions[central];
+        let v = state.velocities[i] - state.velocities[central];
+        let mu = g * mass;
+        let distance = r.length();
+
+        let a = mu / (c2 * distance.powi(3))
+            * ((4.0 * mu / distance - v.length_squared()) * r + 4.0 * r.dot(v) * v);
+
+        accelerations[i] += masses[central] / mass * a;
+        accelerations[central] -= masses[i] / mass * a;
+    }
+}
@@ -1,71 +1,891 @@
-use super::body::Body;
+use super::{
+    body::{self, Body},
+    checkpoint::{Checkpoint, CheckpointError, Decoder, Encoder, F64, USIZE, VEC3},
+    collision::{Collision, CollisionPolicy},
+    cr3bp::Cr3bp,
+    crossing::{Crossing, EventFunction},
+    diagnostics::{Diagnostics, Drift},
+    encounter::{self, Encounter, EncounterRadius},
+    error::SimError,
+    escape::{Escape, EscapeCriterion},
+    event::Event,
+    force::{Bodies, Force, Targets},
+    frame::Frame,
+    gravity::{Gravity, GravityType},
+    integrator::{Integrator, IntegratorType, Model, State},
+    orbit::{Elements, Primary},
+    relativity,
+    units::Units,
+};
 use glam::f64::DVec3;
-use std::f64::consts::PI;
 
-// G = 4πr^2 / M_⊙
-// Solar mass cancels with planet
-const G: f64 = 4.0 * PI * PI;
+// How a system behaves, besides its integrator and gravity backend
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Settings {
+    pub(super) units: Units,
+    pub(super) softening: f64,
+    pub(super) collisions: CollisionPolicy,
+    pub(super) encounters: Option<EncounterRadius>,
+    pub(super) escapes: Option<EscapeCriterion>,
+    pub(super) relativity: bool,
+    pub(super) restricted: Option<Cr3bp>,
+}
 
+// Bodies are stored as a structure of arrays, so the integrators and force kernels can work on
+// the positions, velocities and masses directly
 pub struct System {
-    bodies: Vec<Body>,
+    ids: Vec<usize>,
+    masses: Vec<f64>,
+    radii: Vec<f64>,
+    state: State,
+    time: f64,
+    integrator: Box<dyn Integrator>,
+    // What `integrator` was made from
+    kind: IntegratorType,
+    gravity: Box<dyn Gravity>,
+    // What `gravity` was made from
+    gravity_type: GravityType,
+    forces: Vec<(Box<dyn Force>, Targets)>,
+    functions: Vec<EventFunction>,
+    settings: Settings,
+    events: Vec<Event>,
+    // What drift is measured against
+    initial: Option<Diagnostics>,
+    frame: Frame,
+    // Collision that halted the system, which refuses to step until either body is removed
+    halted: Option<Collision>,
 }
 
 impl System {
-    pub(super) fn new() -> Self {
-        Self { bodies: vec![] }
+    pub(super) fn new(
+        kind: IntegratorType,
+        gravity_type: GravityType,
+        forces: Vec<(Box<dyn Force>, Targets)>,
+        functions: Vec<EventFunction>,
+        settings: Settings,
+    ) -> Self {
+        Self {
+            ids: vec![],
+            masses: vec![],
+            radii: vec![],
+            state: State {
+                positions: vec![],
+                velocities: vec![],
+            },
+            time: 0.0,
+            integrator: kind.integrator(),
+            kind,
+            gravity: gravity_type.gravity(),
+            gravity_type,
+            forces,
+            functions,
+            settings,
+            events: vec![],
+            initial: None,
+            frame: Frame::AsGiven,
+            halted: None,
+        }
+    }
+
+    pub fn bodies(&self) -> impl ExactSizeIterator<Item = Body> + '_ {
+        (0..self.len()).map(|i| self.body(i))
+    }
+
+    pub fn body(&self, index: usize) -> Body {
+        Body::new(
+            self.ids[index],
+            self.state.positions[index],
+            self.state.velocities[index],
+            self.masses[index],
+            self.radii[index],
+        )
+    }
+
+    // Index of the body with the given id, which moves as other bodies are removed
+    pub fn index_of(&self, id: usize) -> Option<usize> {
+        self.ids.iter().position(|&i| i == id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn positions(&self) -> &[DVec3] {
+        &self.state.positions
+    }
+
+    pub fn velocities(&self) -> &[DVec3] {
+        &self.state.velocities
+    }
+
+    pub fn masses(&self) -> &[f64] {
+        &self.masses
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn integrator(&self) -> &dyn Integrator {
+        self.integrator.as_ref()
+    }
+
+    pub fn integrator_type(&self) -> IntegratorType {
+        self.kind
+    }
+
+    pub fn gravity_type(&self) -> GravityType {
+        self.gravity_type
+    }
+
+    pub fn units(&self) -> Units {
+        self.settings.units
+    }
+
+    pub fn softening(&self) -> f64 {
+        self.settings.softening
+    }
+
+    pub fn relativity(&self) -> bool {
+        self.settings.relativity
+    }
+
+    pub fn restricted(&self) -> Option<Cr3bp> {
+        self.settings.restricted
+    }
+
+    // Whether a step of -h exactly undoes a step of h. Takes a reversible integrator, and no
+    // forces beyond Newtonian gravity: Coriolis, drag and 1PN corrections all depend on velocity,
+    // which a kick can't evaluate symmetrically.
+    pub fn is_time_reversible(&self) -> bool {
+        self.kind.is_time_reversible()
+            && self.settings.restricted.is_none()
+            && !self.settings.relativity
+            && self.forces.is_empty()
+    }
+
+    // Jacobi constant of the body with id `id`, in CR3BP mode
+    pub fn jacobi_constant(&self, id: usize) -> Option<f64> {
+        let i = self.index_of(id)?;
+        self.settings.restricted.map(|problem| {
+            problem.jacobi_constant(self.state.positions[i], self.state.velocities[i])
+        })
+    }
+
+    // Osculating elements of the orbit of the body with id `id` about `primary`. None if either
+    // isn't in the system, or the body is its own primary.
+    pub fn elements(&self, id: usize, primary: Primary) -> Option<Elements> {
+        let body = self.body(self.index_of(id)?);
+
+        let primary = match primary {
+            Primary::Body(primary) if primary == id => return None,
+            Primary::Body(primary) => self.body(self.index_of(primary)?),
+            Primary::Interior => {
+                let central = self.body(
+                    (0..self.len()).max_by(|&a, &b| self.masses[a].total_cmp(&self.masses[b]))?,
+                );
+                let distance = (body.position() - central.position()).length();
+
+                let (mut mass, mut moment, mut momentum) = (0.0, DVec3::ZERO, DVec3::ZERO);
+                for b in self.bodies() {
+                    if b.id() != id && (b.position() - central.position()).length() <= distance {
+                        mass += b.mass();
+                        moment += b.mass() * b.position();
+                        momentum += b.mass() * b.velocity();
+                    }
+                }
+                if mass <= 0.0 {
+                    return None;
+                }
+
+                Body::new(id, moment / mass, momentum / mass, mass, 0.0)
+            }
+        };
+
+        Some(body.elements(&primary, self.settings.units))
+    }
+
+    // Frame the bodies were shifted into when the sim was built
+    pub fn frame(&self) -> Frame {
+        self.frame
+    }
+
+    // Collision that halted the system, under `CollisionPolicy::Halt`
+    pub fn halted(&self) -> Option<Collision> {
+        self.halted
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn encounters(&self) -> impl Iterator<Item = &Encounter> {
+        self.events.iter().filter_map(|event| match event {
+            Event::Encounter(encounter) => Some(encounter),
+            _ => None,
+        })
+    }
+
+    // Computed on demand, in O(n²) for the potential energy
+    pub fn diagnostics(&self) -> Diagnostics {
+        let g = self.settings.units.gravitational_constant();
+        let softening2 = self.settings.softening * self.settings.softening;
+        let (positions, velocities, masses) = (self.positions(), self.velocities(), self.masses());
+
+        let mut diagnostics = Diagnostics {
+            time: self.time,
+            kinetic_energy: 0.0,
+            potential_energy: 0.0,
+            angular_momentum: DVec3::ZERO,
+            momentum: DVec3::ZERO,
+            center_of_mass: DVec3::ZERO,
+            mass: masses.iter().sum(),
+        };
+
+        for i in 0..self.len() {
+            let momentum = masses[i] * velocities[i];
+
+            diagnostics.kinetic_energy += momentum.dot(velocities[i]) / 2.0;
+            diagnostics.angular_momentum += positions[i].cross(momentum);
+            diagnostics.momentum += momentum;
+            diagnostics.center_of_mass += masses[i] * positions[i];
+
+            // Test particles have no potential energy
+            if masses[i] == 0.0 {
+                continue;
+            }
+            for j in (i + 1)..self.len() {
+                let r2 = (positions[j] - positions[i]).length_squared() + softening2;
+                diagnostics.potential_energy -= g * masses[i] * masses[j] / r2.sqrt();
+            }
+        }
+
+        if diagnostics.mass > 0.0 {
+            diagnostics.center_of_mass /= diagnostics.mass;
+        }
+
+        diagnostics
+    }
+
+    // Diagnostics drift is measured against, taken when the sim was built
+    pub fn initial_diagnostics(&self) -> Option<&Diagnostics> {
+        self.initial.as_ref()
+    }
+
+    pub fn drift(&self) -> Drift {
+        let current = self.diagnostics();
+        let initial = self.initial.unwrap_or(current);
+
+        let momentum_scale = (0..self.len())
+            .map(|i| self.masses[i] * self.state.velocities[i].length())
+            .sum();
+        let length_scale = if current.mass > 0.0 {
+            (0..self.len())
+                .map(|i| {
+                    self.masses[i] * (self.state.positions[i] - current.center_of_mass).length()
+                })
+                .sum::<f64>()
+                / current.mass
+        } else {
+            0.0
+        };
+
+        Drift::new(&initial, &current, momentum_scale, length_scale)
+    }
+
+    // Moves every body so the origin of `frame` is at rest at the origin. Panics if `frame` is
+    // centered on a body that isn't in the system.
+    pub(super) fn shift_to(&mut self, frame: Frame) {
+        let (position, velocity) = match frame {
+            Frame::AsGiven => (DVec3::ZERO, DVec3::ZERO),
+            Frame::Barycentric => {
+                let diagnostics = self.diagnostics();
+                let velocity = if diagnostics.mass > 0.0 {
+                    diagnostics.momentum / diagnostics.mass
+                } else {
+                    DVec3::ZERO
+                };
+                (diagnostics.center_of_mass, velocity)
+            }
+            Frame::Heliocentric => self
+                .masses
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map_or((DVec3::ZERO, DVec3::ZERO), |(i, _)| {
+                    (self.state.positions[i], self.state.velocities[i])
+                }),
+            Frame::BodyCentric(id) => {
+                let i = self
+                    .index_of(id)
+                    .unwrap_or_else(|| panic!("no body with id {id} to center the frame on"));
+                (self.state.positions[i], self.state.velocities[i])
+            }
+        };
+
+        for x in &mut self.state.positions {
+            *x -= position;
+        }
+        for v in &mut self.state.velocities {
+            *v -= velocity;
+        }
+
+        self.frame = frame;
+        self.integrator.reset();
+    }
+
+    // Measures drift from here on
+    pub(super) fn reset_diagnostics(&mut self) {
+        self.initial = Some(self.diagnostics());
+    }
+
+    pub(super) fn add_force(&mut self, force: Box<dyn Force>, targets: Targets) {
+        self.forces.push((force, targets));
+    }
+
+    pub(super) fn add_event_function(&mut self, function: EventFunction) {
+        self.functions.push(function);
+    }
+
+    // Writes everything needed to carry on exactly where the system is now. Forces and event
+    // functions are code rather than data, so they're left out, as are events not yet drained.
+    pub(super) fn save(&self, encoder: &mut Encoder) {
+        encoder.usize(body::next_id());
+        self.kind.save(encoder);
+        self.gravity_type.save(encoder);
+        self.settings.save(encoder);
+        self.frame.save(encoder);
+        encoder.f64(self.time);
+
+        encoder.usize(self.len());
+        for i in 0..self.len() {
+            encoder.usize(self.ids[i]);
+            encoder.f64(self.masses[i]);
+            encoder.f64(self.radii[i]);
+            encoder.vec3(self.state.positions[i]);
+            encoder.vec3(self.state.velocities[i]);
+        }
+
+        self.initial.save(encoder);
+        self.halted.save(encoder);
+        self.integrator.save(encoder);
+    }
+
+    // Reads back what `save` wrote. Bodies built from here on get ids past any in the checkpoint.
+    pub(super) fn load(decoder: &mut Decoder) -> Result<Self, CheckpointError> {
+        let next_id = decoder.usize()?;
+        let kind = IntegratorType::load(decoder)?;
+        let gravity_type = GravityType::load(decoder)?;
+        let settings = Settings::load(decoder)?;
+
+        let mut system = System::new(kind, gravity_type, vec![], vec![], settings);
+        system.frame = Frame::load(decoder)?;
+        system.time = decoder.f64()?;
+
+        // Id, mass, radius, position and velocity
+        let len = decoder.count(USIZE + 2 * F64 + 2 * VEC3)?;
+        for _ in 0..len {
+            let id = decoder.usize()?;
+            if id >= next_id || system.index_of(id).is_some() {
+                return Err(CheckpointError::Invalid("body id"));
+            }
+
+            let (mass, radius) = (decoder.f64()?, decoder.f64()?);
+            let (position, velocity) = (decoder.vec3()?, decoder.vec3()?);
+            system.insert(Body::new(id, position, velocity, mass, radius));
+        }
+
+        system.initial = Option::load(decoder)?;
+        system.halted = Option::load(decoder)?;
+        system.integrator.load(decoder, len)?;
+        body::reserve_ids(next_id);
+
+        Ok(system)
     }
 
-    pub fn bodies(&self) -> &[Body] {
-        &self.bodies
+    pub(super) fn drain_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
     }
 
     pub(super) fn insert(&mut self, body: Body) {
-        self.bodies.push(body);
+        self.ids.push(body.id());
+        self.masses.push(body.mass());
+        self.radii.push(body.radius());
+        self.state.positions.push(body.position());
+        self.state.velocities.push(body.velocity());
+
+        self.integrator.reset();
     }
 
-    pub(super) fn step(&mut self, step: f64) {
-        for i in 0..self.bodies.len() {
-            let body = &self.bodies[i];
+    pub(super) fn remove(&mut self, id: usize) -> Option<Body> {
+        if self
+            .halted
+            .is_some_and(|collision| collision.bodies.contains(&id))
+        {
+            self.halted = None;
+        }
 
-            let r = body.position();
-            let v = body.velocity();
+        self.index_of(id).map(|index| self.remove_at(index))
+    }
 
-            let half_step = step / 2.0;
-            let sixth_step = step / 6.0;
+    fn remove_at(&mut self, index: usize) -> Body {
+        let body = self.body(index);
 
-            let k1v = self.acceleration(body, DVec3::ZERO);
-            let k1r = v;
-            let k2v = self.acceleration(body, half_step * k1r);
-            let k2r = v + half_step * k1v;
-            let k3v = self.acceleration(body, half_step * k2r);
-            let k3r = v + half_step * k2v;
-            let k4v = self.acceleration(body, step * k3r);
-            let k4r = v + step * k3v;
+        self.ids.remove(index);
+        self.masses.remove(index);
+        self.radii.remove(index);
+        self.state.positions.remove(index);
+        self.state.velocities.remove(index);
 
-            self.bodies[i].apply(
-                r + sixth_step * (k1r + 2.0 * k2r + 2.0 * k3r + k4r),
-                v + sixth_step * (k1v + 2.0 * k2v + 2.0 * k3v + k4v),
-            );
+        self.integrator.reset();
+
+        body
+    }
+
+    pub(super) fn step(&mut self, step: f64) -> Result<(), SimError> {
+        if let Some(collision) = self.halted {
+            return Err(SimError::Collision(collision));
         }
 
-        for body in self.bodies.iter_mut() {
-            body.advance();
+        let field = Field {
+            ids: &self.ids,
+            masses: &self.masses,
+            massive: (0..self.len()).filter(|&i| self.masses[i] != 0.0).collect(),
+            particles: (0..self.len()).filter(|&i| self.masses[i] == 0.0).collect(),
+            gravity: self.gravity.as_ref(),
+            forces: self
+                .forces
+                .iter()
+                .map(|(force, targets)| (force.as_ref(), targets.indices(&self.ids)))
+                .collect(),
+            g: self.settings.units.gravitational_constant(),
+            softening: self.settings.softening,
+            speed_of_light: self
+                .settings
+                .relativity
+                .then(|| self.settings.units.speed_of_light()),
+            restricted: self.settings.restricted,
+        };
+
+        // Bodies with a size can touch in between the ends of the step
+        let start = (self.settings.encounters.is_some()
+            || !self.functions.is_empty()
+            || self.radii.iter().any(|&radius| radius > 0.0))
+        .then(|| self.state.clone());
+
+        self.integrator.step(&mut self.state, &field, step);
+
+        if let Some(start) = &start {
+            for (i, fraction, state) in
+                crossings(&field, self.kind, &self.functions, start, &self.state, step)
+            {
+                let bodies = (0..state.len())
+                    .map(|j| {
+                        Body::new(
+                            self.ids[j],
+                            state.positions[j],
+                            state.velocities[j],
+                            self.masses[j],
+                            self.radii[j],
+                        )
+                    })
+                    .collect();
+
+                self.events.push(Event::Crossing(Crossing {
+                    name: self.functions[i].name(),
+                    time: self.time + fraction * step,
+                    bodies,
+                }));
+            }
+
+            if let Some(radius) = self.settings.encounters {
+                self.encounter(radius, start, step);
+            }
+        }
+        self.time += step;
+
+        if let Some(start) = start {
+            self.collide(start, step)?;
+        }
+        if let Some(criterion) = self.settings.escapes {
+            self.escape(criterion);
         }
+
+        Ok(())
     }
 
-    fn acceleration(&self, body: &Body, offset: DVec3) -> DVec3 {
-        let mut acceleration = DVec3::ZERO;
-        let position = body.position() + offset;
+    // Logs every pair passing within `radius` of each other over the step from `start`
+    fn encounter(&mut self, radius: EncounterRadius, start: &State, step: f64) {
+        let central = self
+            .masses
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i);
+
+        for i in 0..self.len() {
+            for j in (i + 1)..self.len() {
+                let threshold = match (radius, central) {
+                    (EncounterRadius::Distance(distance), _) => distance,
+                    (EncounterRadius::Hill(_), Some(c)) if c == i || c == j => continue,
+                    (EncounterRadius::Hill(n), Some(c)) => {
+                        let r = |k: usize| (start.positions[k] - start.positions[c]).length();
+                        let mass = (self.masses[i] + self.masses[j]) / (3.0 * self.masses[c]);
+                        n * mass.cbrt() * (r(i) + r(j)) / 2.0
+                    }
+                    (EncounterRadius::Hill(_), None) => continue,
+                };
+
+                let relative = |state: &State| {
+                    (
+                        state.positions[j] - state.positions[i],
+                        state.velocities[j] - state.velocities[i],
+                    )
+                };
 
-        for other_body in &self.bodies {
-            if body.id() == other_body.id() {
+                if let Some((t, distance, speed)) =
+                    encounter::closest_approach(relative(start), relative(&self.state), step)
+                {
+                    if distance <= threshold {
+                        self.events.push(Event::Encounter(Encounter {
+                            time: self.time + t * step,
+                            bodies: [self.ids[i], self.ids[j]],
+                            distance,
+                            speed,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+
+    // Removes every body meeting `criterion`, logging each
+    fn escape(&mut self, criterion: EscapeCriterion) {
+        let g = self.settings.units.gravitational_constant();
+        let positions = &self.state.positions;
+        let velocities = &self.state.velocities;
+
+        let mass: f64 = self.masses.iter().sum();
+        let (moment, momentum) = (0..self.len()).fold((DVec3::ZERO, DVec3::ZERO), |(p, q), i| {
+            (
+                p + self.masses[i] * positions[i],
+                q + self.masses[i] * velocities[i],
+            )
+        });
+
+        let central = self
+            .masses
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i);
+
+        let mut escapes = vec![];
+        for i in 0..self.len() {
+            let rest = mass - self.masses[i];
+            if Some(i) == central || rest <= 0.0 {
+                continue;
+            }
+
+            let position = positions[i] - (moment - self.masses[i] * positions[i]) / rest;
+            let velocity = velocities[i] - (momentum - self.masses[i] * velocities[i]) / rest;
+            let distance = position.length();
+            let energy = velocity.length_squared() / 2.0 - g * (rest + self.masses[i]) / distance;
+
+            let escaped = match criterion {
+                EscapeCriterion::Distance(cutoff) => distance > cutoff,
+                EscapeCriterion::Unbound => energy > 0.0,
+                EscapeCriterion::UnboundBeyond(cutoff) => energy > 0.0 && distance > cutoff,
+            };
+
+            if escaped {
+                escapes.push((
+                    i,
+                    Escape {
+                        time: self.time,
+                        body: self.ids[i],
+                        position,
+                        velocity,
+                        energy,
+                    },
+                ));
+            }
+        }
+
+        // Back to front, so the indices left to remove stay valid
+        for (i, escape) in escapes.into_iter().rev() {
+            self.remove_at(i);
+            self.events.push(Event::Escape(escape));
+        }
+    }
+
+    // First pair of bodies touching, that still needs resolving, with the fraction of the step
+    // from `start` they touched at and the direction from the first to the second there. Bodies
+    // can pass through each other within a step, touching on the way.
+    fn contact(&self, start: &State, step: f64) -> Option<(usize, usize, f64, DVec3)> {
+        let positions = &self.state.positions;
+        let velocities = &self.state.velocities;
+
+        for i in 0..self.len() {
+            if self.radii[i] <= 0.0 {
                 continue;
             }
 
-            let x = other_body.position() - position;
-            acceleration += G * other_body.mass() / x.length().powi(3) * x;
+            for j in (i + 1)..self.len() {
+                if self.radii[j] <= 0.0 {
+                    continue;
+                }
+                let reach = self.radii[i] + self.radii[j];
+
+                let x = positions[j] - positions[i];
+                if x.length() <= reach {
+                    // Bodies that already bounced are moving apart, even while still overlapping
+                    let approaching = (velocities[i] - velocities[j]).dot(x) > 0.0;
+                    if approaching || self.settings.collisions != CollisionPolicy::Bounce {
+                        return Some((i, j, 1.0, x.normalize_or_zero()));
+                    }
+                    continue;
+                }
+
+                let relative = |state: &State| {
+                    (
+                        state.positions[j] - state.positions[i],
+                        state.velocities[j] - state.velocities[i],
+                    )
+                };
+                if let Some((fraction, x)) =
+                    encounter::contact(relative(start), relative(&self.state), step, reach)
+                {
+                    return Some((i, j, fraction, x.normalize_or_zero()));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn collide(&mut self, mut start: State, step: f64) -> Result<(), SimError> {
+        while let Some((i, j, fraction, n)) = self.contact(&start, step) {
+            let (a, b) = (self.body(i), self.body(j));
+            let mass = a.mass() + b.mass();
+            // Bodies without mass still merge into their average
+            let (wa, wb) = if mass > 0.0 {
+                (a.mass() / mass, b.mass() / mass)
+            } else {
+                (0.5, 0.5)
+            };
+
+            // Left of the step after touching
+            let after = (1.0 - fraction) * step;
+            let center = wa * a.position() + wb * b.position();
+            let velocity = wa * a.velocity() + wb * b.velocity();
+
+            let mut collision = Collision {
+                time: self.time - after,
+                bodies: [a.id(), b.id()],
+                position: center - after * velocity,
+                impact_speed: (a.velocity() - b.velocity()).length(),
+                merged: None,
+            };
+
+            match self.settings.collisions {
+                CollisionPolicy::Merge => {
+                    // The heavier body carries on with the combined properties
+                    let (survivor, absorbed) = if b.mass() > a.mass() { (j, i) } else { (i, j) };
+
+                    self.state.positions[survivor] = center;
+                    self.state.velocities[survivor] = velocity;
+                    self.masses[survivor] = mass;
+                    self.radii[survivor] = (a.radius().powi(3) + b.radius().powi(3)).cbrt();
+
+                    collision.merged = Some(self.ids[survivor]);
+                    // A new body, with no path over the step behind it
+                    start.positions[survivor] = center;
+                    start.velocities[survivor] = velocity;
+                    self.remove_at(absorbed);
+                    start.positions.remove(absorbed);
+                    start.velocities.remove(absorbed);
+                }
+                CollisionPolicy::Bounce => {
+                    let u = (a.velocity() - b.velocity()).dot(n);
+                    let (kick_a, kick_b) = (-2.0 * wb * u * n, 2.0 * wa * u * n);
+
+                    // Moving apart over the rest of the step
+                    self.state.velocities[i] += kick_a;
+                    self.state.velocities[j] += kick_b;
+                    self.state.positions[i] += after * kick_a;
+                    self.state.positions[j] += after * kick_b;
+
+                    // Resolved, so not to be found passing through each other again
+                    for k in [i, j] {
+                        start.positions[k] = self.state.positions[k];
+                        start.velocities[k] = self.state.velocities[k];
+                    }
+                }
+                CollisionPolicy::Halt => {
+                    self.halted = Some(collision);
+                    self.events.push(Event::Collision(collision));
+                    return Err(SimError::Collision(collision));
+                }
+            }
+
+            self.events.push(Event::Collision(collision));
         }
 
-        acceleration
+        Ok(())
     }
 }
+
+// Everything accelerating the bodies of a system
+struct Field<'a> {
+    ids: &'a [usize],
+    masses: &'a [f64],
+    // Indices of bodies with and without mass
+    massive: Vec<usize>,
+    particles: Vec<usize>,
+    gravity: &'a dyn Gravity,
+    // Indices of the bodies each acts on
+    forces: Vec<(&'a dyn Force, Vec<usize>)>,
+    g: f64,
+    softening: f64,
+    // For the post-Newtonian correction, when enabled
+    speed_of_light: Option<f64>,
+    // In place of gravity between the bodies, when enabled
+    restricted: Option<Cr3bp>,
+}
+
+impl Field<'_> {
+    fn bodies<'b>(&'b self, state: &'b State) -> Bodies<'b> {
+        Bodies {
+            ids: self.ids,
+            masses: self.masses,
+            positions: &state.positions,
+            velocities: &state.velocities,
+            g: self.g,
+        }
+    }
+
+    // Between massive bodies, then on test particles from the massive bodies alone
+    fn gravity(&self, state: &State) -> Vec<DVec3> {
+        if self.particles.is_empty() {
+            return self.gravity.accelerations(
+                &state.positions,
+                self.masses,
+                self.g,
+                self.softening,
+            );
+        }
+
+        let positions = |indices: &[usize]| {
+            indices
+                .iter()
+                .map(|&i| state.positions[i])
+                .collect::<Vec<_>>()
+        };
+        let (massive, particles) = (positions(&self.massive), positions(&self.particles));
+        let masses = self
+            .massive
+            .iter()
+            .map(|&i| self.masses[i])
+            .collect::<Vec<_>>();
+
+        let mut accelerations = vec![DVec3::ZERO; state.len()];
+        let pulls = self
+            .gravity
+            .accelerations(&massive, &masses, self.g, self.softening);
+        for (&i, a) in self.massive.iter().zip(pulls) {
+            accelerations[i] = a;
+        }
+        let pulls =
+            self.gravity
+                .accelerations_on(&particles, &massive, &masses, self.g, self.softening);
+        for (&i, a) in self.particles.iter().zip(pulls) {
+            accelerations[i] = a;
+        }
+
+        accelerations
+    }
+}
+
+impl Model for Field<'_> {
+    fn masses(&self) -> &[f64] {
+        self.masses
+    }
+
+    fn gravitational_constant(&self) -> f64 {
+        self.g
+    }
+
+    fn softening(&self) -> f64 {
+        self.softening
+    }
+
+    fn accelerations(&self, state: &State) -> Vec<DVec3> {
+        let mut accelerations = match self.restricted {
+            Some(problem) => (0..state.len())
+                .map(|i| problem.acceleration(state.positions[i], state.velocities[i]))
+                .collect(),
+            None => self.gravity(state),
+        };
+
+        if let Some(c) = self.speed_of_light {
+            relativity::correct(&mut accelerations, state, self.masses, self.g, c);
+        }
+
+        let bodies = self.bodies(state);
+        for (force, targets) in &self.forces {
+            force.accelerate(&bodies, targets, &mut accelerations);
+        }
+
+        accelerations
+    }
+}
+
+// Zero crossings of each of `functions` over the step from `start` to `end`, as the index of the
+// function, the fraction of the step and the state there. Each is found by bisection, integrating
+// from `start` with a fresh integrator so the one stepping the system is left untouched.
+fn crossings(
+    field: &Field,
+    kind: IntegratorType,
+    functions: &[EventFunction],
+    start: &State,
+    end: &State,
+    step: f64,
+) -> Vec<(usize, f64, State)> {
+    let advance = |fraction: f64| {
+        let mut state = start.clone();
+        kind.integrator().step(&mut state, field, fraction * step);
+        state
+    };
+
+    let mut crossings = vec![];
+    for (i, function) in functions.iter().enumerate() {
+        let before = function.value(&field.bodies(start));
+        if !function.crosses(before, function.value(&field.bodies(end))) {
+            continue;
+        }
+
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..128 {
+            if (high - low) * step.abs() <= function.tolerance() {
+                break;
+            }
+
+            let mid = (low + high) / 2.0;
+            if function.crosses(before, function.value(&field.bodies(&advance(mid)))) {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+
+        let fraction = (low + high) / 2.0;
+        crossings.push((i, fraction, advance(fraction)));
+    }
+
+    crossings
+}
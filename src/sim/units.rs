@@ -0,0 +1,91 @@
+use glam::f64::DVec3;
+use std::f64::consts::PI;
+
+// CODATA 2018, m³ / (kg s²)
+const G: f64 = 6.674_30e-11;
+// m / s
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+// IAU 2012, m
+const ASTRONOMICAL_UNIT: f64 = 1.495_978_707e11;
+// IAU 2015 nominal solar mass parameter, m³ / s²
+const SOLAR_MASS_PARAMETER: f64 = 1.327_124_4e20;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Units {
+    // AU, solar masses and years of 365.2569 days, so that G = 4π²
+    Astronomical,
+    // m, kg and s
+    Si,
+    // km, kg and s
+    Kilometers,
+    // G = 1, with the units of length (m) and mass (kg) given. The unit of time follows.
+    NBody { length: f64, mass: f64 },
+}
+
+impl Units {
+    pub fn gravitational_constant(&self) -> f64 {
+        match self {
+            Units::Astronomical => 4.0 * PI * PI,
+            Units::Si => G,
+            Units::Kilometers => G * 1e-9,
+            Units::NBody { .. } => 1.0,
+        }
+    }
+
+    // Unit of length in m
+    pub fn length(&self) -> f64 {
+        match self {
+            Units::Astronomical => ASTRONOMICAL_UNIT,
+            Units::Si => 1.0,
+            Units::Kilometers => 1e3,
+            Units::NBody { length, .. } => *length,
+        }
+    }
+
+    // Unit of mass in kg
+    pub fn mass(&self) -> f64 {
+        match self {
+            Units::Astronomical => SOLAR_MASS_PARAMETER / G,
+            Units::Si | Units::Kilometers => 1.0,
+            Units::NBody { mass, .. } => *mass,
+        }
+    }
+
+    // Unit of time in s
+    pub fn time(&self) -> f64 {
+        match self {
+            Units::Si | Units::Kilometers => 1.0,
+            // G' = G M T² / L³
+            _ => (self.gravitational_constant() * self.length().powi(3) / (G * self.mass())).sqrt(),
+        }
+    }
+
+    // Unit of velocity in m / s
+    pub fn velocity(&self) -> f64 {
+        self.length() / self.time()
+    }
+
+    pub fn speed_of_light(&self) -> f64 {
+        SPEED_OF_LIGHT / self.velocity()
+    }
+
+    pub fn convert_length(&self, length: f64, to: Units) -> f64 {
+        length * self.length() / to.length()
+    }
+
+    pub fn convert_mass(&self, mass: f64, to: Units) -> f64 {
+        mass * self.mass() / to.mass()
+    }
+
+    pub fn convert_time(&self, time: f64, to: Units) -> f64 {
+        time * self.time() / to.time()
+    }
+
+    pub fn convert_position(&self, position: DVec3, to: Units) -> DVec3 {
+        position * self.length() / to.length()
+    }
+
+    pub fn convert_velocity(&self, velocity: DVec3, to: Units) -> DVec3 {
+        velocity * self.velocity() / to.velocity()
+    }
+}
@@ -0,0 +1,202 @@
+use planet_sim::sim::{
+    body::BodyBuilder,
+    checkpoint::CheckpointError,
+    cr3bp::Cr3bp,
+    gravity::{fmm, GravityType},
+    integrator::IntegratorType,
+    orbit::Orbit,
+    units::Units,
+    Sim, SimBuilder,
+};
+
+fn planets(integrator: IntegratorType) -> Sim {
+    let sun = BodyBuilder::new(1.0).build();
+    let planet = |mass, a, e, i| {
+        BodyBuilder::new(mass)
+            .with_orbit(
+                &sun,
+                Orbit::new(a, e, i, 0.3, 1.1, 2.0),
+                Units::Astronomical,
+            )
+            .build()
+    };
+    let bodies = vec![
+        sun,
+        planet(3e-6, 1.0, 0.0167, 0.0),
+        planet(9.5e-4, 5.2, 0.0489, 0.0228),
+        planet(0.0, 2.7, 0.2, 0.1),
+    ];
+
+    SimBuilder::new(integrator).with_bodies(bodies).build()
+}
+
+fn assert_identical(a: &Sim, b: &Sim) {
+    let (a, b) = (a.system(), b.system());
+
+    assert_eq!(a.time().to_bits(), b.time().to_bits());
+    for (a, b) in a.bodies().zip(b.bodies()) {
+        assert_eq!(a.id(), b.id());
+        assert_eq!(
+            a.position().to_array().map(f64::to_bits),
+            b.position().to_array().map(f64::to_bits)
+        );
+        assert_eq!(
+            a.velocity().to_array().map(f64::to_bits),
+            b.velocity().to_array().map(f64::to_bits)
+        );
+    }
+}
+
+#[test]
+fn restored_runs_match_uninterrupted_ones() {
+    for integrator in [
+        IntegratorType::Ias15 { epsilon: 1e-9 },
+        IntegratorType::DormandPrince {
+            atol: 1e-10,
+            rtol: 1e-10,
+        },
+        IntegratorType::WisdomHolman,
+    ] {
+        let mut original = planets(integrator);
+        for _ in 0..50 {
+            original.step(0.05).unwrap();
+        }
+
+        let mut restored = Sim::restore(&original.checkpoint()).unwrap();
+        assert_identical(&original, &restored);
+
+        for _ in 0..50 {
+            original.step(0.05).unwrap();
+            restored.step(0.05).unwrap();
+        }
+        assert_identical(&original, &restored);
+    }
+}
+
+#[test]
+fn many_bodies_round_trip() {
+    let sun = BodyBuilder::new(1.0).build();
+    let bodies = std::iter::once(sun)
+        .chain((1..40).map(|k| {
+            let k = k as f64;
+            BodyBuilder::new(1e-9 * k)
+                .with_orbit(
+                    &sun,
+                    Orbit::new(1.0 + 0.1 * k, 0.01 * k, 0.01 * k, k, 2.0 * k, 3.0 * k),
+                    Units::Astronomical,
+                )
+                .build()
+        }))
+        .collect::<Vec<_>>();
+
+    // Every body is a good share of the checkpoint when the integrator carries nothing else
+    for integrator in [
+        IntegratorType::Euler,
+        IntegratorType::SemiImplicitEuler,
+        IntegratorType::Leapfrog,
+        IntegratorType::Rk4,
+        IntegratorType::Yoshida,
+        IntegratorType::WisdomHolman,
+        IntegratorType::DormandPrince {
+            atol: 1e-10,
+            rtol: 1e-10,
+        },
+    ] {
+        let mut sim = SimBuilder::new(integrator)
+            .with_bodies(bodies.clone())
+            .build();
+        sim.step(0.1).unwrap();
+
+        let restored = Sim::restore(&sim.checkpoint()).unwrap();
+        assert_eq!(restored.system().len(), 40);
+        assert_identical(&sim, &restored);
+    }
+}
+
+#[test]
+fn checkpoints_round_trip_through_files() {
+    let mut sim = planets(IntegratorType::Ias15 { epsilon: 1e-9 });
+    sim.step(1.0).unwrap();
+
+    let path = std::env::temp_dir().join(format!("planet_sim_{}.ckpt", std::process::id()));
+    sim.save(&path).unwrap();
+    let loaded = Sim::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let loaded = loaded.unwrap();
+    assert_identical(&sim, &loaded);
+    assert_eq!(loaded.checkpoint(), sim.checkpoint());
+
+    // New bodies don't reuse ids from the checkpoint
+    let next = BodyBuilder::new(1.0).build().id();
+    assert!(sim.system().bodies().all(|body| body.id() < next));
+}
+
+#[test]
+fn bad_checkpoints_are_rejected() {
+    let bytes = planets(IntegratorType::Rk4).checkpoint();
+
+    assert!(matches!(
+        Sim::restore(b"not a checkpoint"),
+        Err(CheckpointError::NotACheckpoint)
+    ));
+    assert!(matches!(
+        Sim::restore(&bytes[..bytes.len() - 1]),
+        Err(CheckpointError::Truncated)
+    ));
+
+    let mut newer = bytes.clone();
+    newer[4] += 1;
+    assert!(matches!(
+        Sim::restore(&newer),
+        Err(CheckpointError::Version(_))
+    ));
+}
+
+// Overwrites the only occurrence of `from` in `bytes`
+fn patch(bytes: &[u8], from: &[u8], to: &[u8]) -> Vec<u8> {
+    let mut at = bytes
+        .windows(from.len())
+        .enumerate()
+        .filter(|(_, w)| *w == from);
+    let (i, _) = at.next().expect("nothing to patch");
+    assert!(at.next().is_none(), "more than one place to patch");
+
+    let mut patched = bytes.to_vec();
+    patched[i..i + from.len()].copy_from_slice(to);
+    patched
+}
+
+#[test]
+fn out_of_range_settings_are_rejected() {
+    // The backend's tag followed by its order
+    let fmm = |order: u64| [&[2], &order.to_le_bytes()[..]].concat();
+    let bytes = SimBuilder::new(IntegratorType::Rk4)
+        .with_bodies(vec![BodyBuilder::new(1.0).build()])
+        .with_gravity(GravityType::Fmm { order: 7 })
+        .build()
+        .checkpoint();
+
+    for order in [0, fmm::MAX_ORDER as u64 + 1, u64::MAX] {
+        assert!(matches!(
+            Sim::restore(&patch(&bytes, &fmm(7), &fmm(order))),
+            Err(CheckpointError::Invalid("gravity backend"))
+        ));
+    }
+    assert!(Sim::restore(&patch(&bytes, &fmm(7), &fmm(fmm::MAX_ORDER as u64))).is_ok());
+
+    let mu = 0.012_150_585_f64;
+    let bytes = SimBuilder::new(IntegratorType::Rk4)
+        .with_bodies(vec![BodyBuilder::test_particle().build()])
+        .with_restricted(Cr3bp::new(mu))
+        .build()
+        .checkpoint();
+
+    for bad in [0.0, -0.1, 0.6, f64::NAN] {
+        assert!(matches!(
+            Sim::restore(&patch(&bytes, &mu.to_le_bytes(), &bad.to_le_bytes())),
+            Err(CheckpointError::Invalid("restricted problem"))
+        ));
+    }
+    assert!(Sim::restore(&patch(&bytes, &mu.to_le_bytes(), &0.5_f64.to_le_bytes())).is_ok());
+}
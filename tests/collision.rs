@@ -0,0 +1,153 @@
+use glam::DVec3;
+use planet_sim::sim::{
+    body::{Body, BodyBuilder},
+    collision::CollisionPolicy,
+    error::SimError,
+    event::Event,
+    integrator::IntegratorType,
+    Sim, SimBuilder,
+};
+
+// Two bodies closing head-on along x, far enough apart to meet in a few steps, without gravity
+// to speak of
+fn head_on(policy: CollisionPolicy) -> (Sim, [Body; 2]) {
+    let bodies = [(1e-9, -1.0, 2.0, 0.2), (3e-9, 1.0, -1.0, 0.3)].map(|(mass, x, v, radius)| {
+        BodyBuilder::new(mass)
+            .with_position(DVec3::new(x, 0.0, 0.0))
+            .with_velocity(DVec3::new(v, 0.0, 0.0))
+            .with_radius(radius)
+            .build()
+    });
+    let sim = SimBuilder::new(IntegratorType::Leapfrog)
+        .with_bodies(bodies.to_vec())
+        .with_collisions(policy)
+        .build();
+
+    (sim, bodies)
+}
+
+fn collisions(sim: &mut Sim) -> usize {
+    sim.drain_events()
+        .iter()
+        .filter(|event| matches!(event, Event::Collision(_)))
+        .count()
+}
+
+#[test]
+fn merging_conserves_mass_momentum_and_volume() {
+    let (mut sim, [a, b]) = head_on(CollisionPolicy::Merge);
+    for _ in 0..100 {
+        sim.step(0.01).unwrap();
+    }
+
+    let system = sim.system();
+    assert_eq!(system.len(), 1);
+    assert_eq!(collisions(&mut sim), 1);
+
+    // Into the heavier body
+    let merged = sim.system().body(0);
+    let momentum = a.mass() * a.velocity() + b.mass() * b.velocity();
+    assert_eq!(merged.id(), b.id());
+    assert!((merged.mass() - (a.mass() + b.mass())).abs() < 1e-24);
+    assert!((merged.mass() * merged.velocity() - momentum).length() < 1e-20);
+    assert!((merged.radius().powi(3) - (0.2f64.powi(3) + 0.3f64.powi(3))).abs() < 1e-15);
+}
+
+#[test]
+fn bouncing_is_elastic() {
+    let (mut sim, [a, b]) = head_on(CollisionPolicy::Bounce);
+    for _ in 0..100 {
+        sim.step(0.01).unwrap();
+    }
+
+    let system = sim.system();
+    assert_eq!(system.len(), 2);
+    assert_eq!(collisions(&mut sim), 1);
+
+    let (after_a, after_b) = (sim.system().body(0), sim.system().body(1));
+    let momentum = |x: &Body, y: &Body| x.mass() * x.velocity() + y.mass() * y.velocity();
+    let energy = |x: &Body, y: &Body| {
+        x.mass() * x.velocity().length_squared() + y.mass() * y.velocity().length_squared()
+    };
+    assert!((momentum(&after_a, &after_b) - momentum(&a, &b)).length() < 1e-20);
+    assert!((energy(&after_a, &after_b) - energy(&a, &b)).abs() < 1e-18);
+    // Moving apart
+    assert!(after_a.velocity().x < 0.0 && after_b.velocity().x > 0.0);
+}
+
+#[test]
+fn halting_stops_the_sim_until_a_body_is_removed() {
+    let (mut sim, [a, _]) = head_on(CollisionPolicy::Halt);
+    let error = loop {
+        if let Err(error) = sim.step(0.01) {
+            break error;
+        }
+    };
+    let SimError::Collision(collision) = error;
+    assert_eq!(sim.system().halted().unwrap().time, collision.time);
+
+    // Further steps fail the same way without moving anything
+    let (time, positions) = (sim.system().time(), sim.system().positions().to_vec());
+    for _ in 0..10 {
+        assert!(sim.step(0.01).is_err());
+    }
+    assert_eq!(sim.system().time(), time);
+    assert_eq!(sim.system().positions(), &positions[..]);
+    assert_eq!(collisions(&mut sim), 1);
+
+    // Even once restored
+    let restored = Sim::restore(&sim.checkpoint()).unwrap();
+    assert_eq!(restored.system().halted().unwrap().bodies, collision.bodies);
+
+    sim.remove(a.id());
+    assert!(sim.system().halted().is_none());
+    sim.step(0.01).unwrap();
+}
+
+// Two bodies crossing each other's full width in a single step
+fn pass_through(policy: CollisionPolicy) -> Sim {
+    let bodies = [(-0.75, 100.0), (0.75, -100.0)].map(|(x, v)| {
+        BodyBuilder::new(1e-12)
+            .with_position(DVec3::new(x, 0.0, 0.0))
+            .with_velocity(DVec3::new(v, 0.0, 0.0))
+            .with_radius(0.1)
+            .build()
+    });
+    let mut sim = SimBuilder::new(IntegratorType::Leapfrog)
+        .with_bodies(bodies.to_vec())
+        .with_collisions(policy)
+        .build();
+    // Failing if halted
+    let _ = sim.step(0.01);
+
+    sim
+}
+
+#[test]
+fn bodies_cannot_pass_through_each_other() {
+    let mut merged = pass_through(CollisionPolicy::Merge);
+    assert_eq!(merged.system().len(), 1);
+    assert_eq!(collisions(&mut merged), 1);
+
+    // Touching 0.65 of the way through the step, each having closed 0.65 of the gap
+    let mut bounced = pass_through(CollisionPolicy::Bounce);
+    let events = bounced.drain_events();
+    let Some(Event::Collision(collision)) = events.first() else {
+        panic!("{events:?}");
+    };
+    assert!(
+        (collision.time - 0.0065).abs() < 1e-12,
+        "{}",
+        collision.time
+    );
+    assert!(collision.position.length() < 1e-12);
+
+    // And back out again, each on its own side
+    let (a, b) = (bounced.system().body(0), bounced.system().body(1));
+    assert!((a.position().x + 0.45).abs() < 1e-9, "{}", a.position());
+    assert!((b.position().x - 0.45).abs() < 1e-9, "{}", b.position());
+    assert!(a.velocity().x < 0.0 && b.velocity().x > 0.0);
+
+    let halted = pass_through(CollisionPolicy::Halt);
+    assert!(halted.system().halted().is_some());
+}
@@ -0,0 +1,97 @@
+use glam::DVec3;
+use planet_sim::sim::{
+    body::BodyBuilder, cr3bp::Cr3bp, integrator::IntegratorType, Sim, SimBuilder,
+};
+use std::f64::consts::FRAC_PI_3;
+
+// Earth and moon
+const MU: f64 = 0.012_150_585;
+
+fn restricted(integrator: IntegratorType, position: DVec3, velocity: DVec3) -> (Sim, usize) {
+    let particle = BodyBuilder::test_particle()
+        .with_position(position)
+        .with_velocity(velocity)
+        .build();
+    let sim = SimBuilder::new(integrator)
+        .with_bodies(vec![particle])
+        .with_restricted(Cr3bp::new(MU))
+        .build();
+
+    (sim, particle.id())
+}
+
+#[test]
+fn lagrange_points_are_equilibria() {
+    let problem = Cr3bp::new(MU);
+    let points = problem.lagrange_points();
+
+    for (i, point) in points.iter().enumerate() {
+        let acceleration = problem.acceleration(*point, DVec3::ZERO);
+        assert!(acceleration.length() < 1e-12, "L{}: {acceleration}", i + 1);
+    }
+
+    // Against published values for the Earth-moon system
+    for (point, x) in points.iter().zip([0.836_915, 1.155_682, -1.005_063]) {
+        assert!((point.x - x).abs() < 1e-6, "{point}");
+    }
+    // Equilateral with the primaries
+    let [p1, p2] = problem.primaries();
+    for point in &points[3..] {
+        assert!(((*point - p1).length() - 1.0).abs() < 1e-15);
+        assert!(((*point - p2).length() - 1.0).abs() < 1e-15);
+    }
+}
+
+#[test]
+fn jacobi_constant_is_conserved() {
+    // Looping around both primaries
+    let (mut sim, id) = restricted(
+        IntegratorType::Ias15 { epsilon: 1e-9 },
+        DVec3::new(0.5, 0.0, 0.05),
+        DVec3::new(0.0, 0.9, 0.0),
+    );
+    let initial = sim.system().jacobi_constant(id).unwrap();
+    for _ in 0..200 {
+        sim.step(0.05).unwrap();
+    }
+
+    let error = (sim.system().jacobi_constant(id).unwrap() - initial).abs();
+    assert!(error < 1e-10, "Jacobi constant changed by {error}");
+}
+
+#[test]
+fn to_inertial_undoes_the_rotation() {
+    let problem = Cr3bp::new(MU);
+    let l4 = problem.lagrange_points()[3];
+
+    // Stable for a mass ratio this small, so a particle at rest there stays there
+    let (mut sim, _) = restricted(IntegratorType::Ias15 { epsilon: 1e-9 }, l4, DVec3::ZERO);
+    for _ in 0..100 {
+        sim.step(FRAC_PI_3 / 100.0).unwrap();
+    }
+
+    let system = sim.system();
+    let body = system.body(0);
+    let (position, velocity) = problem.to_inertial(body.position(), body.velocity(), system.time());
+
+    // A sixth of an orbit about the barycenter, at unit angular velocity
+    let (sin, cos) = FRAC_PI_3.sin_cos();
+    let expected = DVec3::new(cos * l4.x - sin * l4.y, sin * l4.x + cos * l4.y, 0.0);
+    assert!((position - expected).length() < 1e-9, "{position}");
+    assert!(
+        (velocity - DVec3::Z.cross(expected)).length() < 1e-9,
+        "{velocity}"
+    );
+
+    // The primaries are fixed in the rotating frame
+    let [_, p2] = problem.primaries();
+    let (position, velocity) = problem.to_inertial(p2, DVec3::ZERO, FRAC_PI_3);
+    assert!((position - p2.length() * DVec3::new(cos, sin, 0.0)).length() < 1e-15);
+    assert!((velocity.length() - p2.length()).abs() < 1e-15);
+}
+
+#[test]
+#[should_panic(expected = "Wisdom-Holman")]
+fn wisdom_holman_is_rejected() {
+    restricted(IntegratorType::WisdomHolman, DVec3::X, DVec3::ZERO);
+}
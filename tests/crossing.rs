@@ -0,0 +1,93 @@
+use planet_sim::sim::{
+    body::{Body, BodyBuilder},
+    crossing::{Crossing, Direction, EventFunction},
+    event::Event,
+    integrator::IntegratorType,
+    kepler,
+    orbit::Orbit,
+    units::Units,
+    SimBuilder,
+};
+use std::f64::consts::TAU;
+
+// Periapses and ascending nodes of a test particle on an inclined, eccentric orbit of a year,
+// starting from a mean anomaly of 1, over two and a half years
+fn crossings(integrator: IntegratorType) -> (Body, Vec<Crossing>) {
+    let sun = BodyBuilder::new(1.0).build();
+    let planet = BodyBuilder::test_particle()
+        .with_orbit(
+            &sun,
+            Orbit::new(1.0, 0.3, 0.4, 1.0, 2.0, 1.0),
+            Units::Astronomical,
+        )
+        .build();
+
+    let periapsis = EventFunction::new("periapsis", Direction::Rising, 1e-12, |b| {
+        (b.positions[1] - b.positions[0]).dot(b.velocities[1] - b.velocities[0])
+    });
+    let node = EventFunction::new("node", Direction::Rising, 1e-12, |b| {
+        b.positions[1].z - b.positions[0].z
+    });
+    let mut sim = SimBuilder::new(integrator)
+        .with_bodies(vec![sun, planet])
+        .with_event_function(periapsis)
+        .with_event_function(node)
+        .build();
+    for _ in 0..250 {
+        sim.step(0.01).unwrap();
+    }
+
+    let crossings = sim
+        .drain_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::Crossing(crossing) => Some(crossing),
+            _ => None,
+        })
+        .collect();
+
+    (planet, crossings)
+}
+
+fn check(integrator: IntegratorType, time_tolerance: f64, position_tolerance: f64) {
+    let (planet, crossings) = crossings(integrator);
+    let mu = Units::Astronomical.gravitational_constant();
+
+    // One of each a year, rising only
+    let named = |name| crossings.iter().filter(move |c| c.name == name);
+    assert_eq!(named("periapsis").count(), 2, "{integrator:?}");
+    assert_eq!(named("node").count(), 2, "{integrator:?}");
+
+    // The rest of the first orbit, from M = 1 to 2π
+    let first = (TAU - 1.0) / TAU;
+    for (k, crossing) in named("periapsis").enumerate() {
+        let error = crossing.time - (first + k as f64);
+        assert!(error.abs() < time_tolerance, "{integrator:?}: {error}");
+    }
+
+    for crossing in named("node") {
+        let r = crossing.bodies[1].position() - crossing.bodies[0].position();
+        let v = crossing.bodies[1].velocity() - crossing.bodies[0].velocity();
+        let (expected, _) =
+            kepler::propagate(planet.position(), planet.velocity(), mu, crossing.time);
+
+        assert!(r.z.abs() < 1e-12, "{integrator:?}: z = {}", r.z);
+        assert!(v.z > 0.0);
+        assert!(
+            (r - expected).length() < position_tolerance,
+            "{integrator:?}: {r} against {expected}"
+        );
+    }
+}
+
+#[test]
+fn crossings_are_located_exactly() {
+    check(IntegratorType::Ias15 { epsilon: 1e-9 }, 1e-12, 1e-12);
+    check(IntegratorType::WisdomHolman, 1e-12, 1e-12);
+}
+
+#[test]
+fn crossings_are_as_good_as_the_integrator() {
+    check(IntegratorType::Rk4, 1e-6, 1e-4);
+    check(IntegratorType::Leapfrog, 2e-3, 3e-2);
+}
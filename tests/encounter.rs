@@ -0,0 +1,83 @@
+use glam::DVec3;
+use planet_sim::sim::{
+    body::BodyBuilder,
+    encounter::{Encounter, EncounterRadius},
+    integrator::IntegratorType,
+    SimBuilder,
+};
+
+// A test particle flying past another at rest in a straight line, closest at time 1 and distance
+// 0.05, at unit speed
+fn flyby(radius: EncounterRadius, step: f64) -> Vec<Encounter> {
+    let still = BodyBuilder::test_particle().build();
+    let passing = BodyBuilder::test_particle()
+        .with_position(DVec3::new(-1.0, 0.05, 0.0))
+        .with_velocity(DVec3::X)
+        .build();
+    let mut sim = SimBuilder::new(IntegratorType::Leapfrog)
+        .with_bodies(vec![still, passing])
+        .with_encounters(radius)
+        .build();
+    while sim.system().time() < 2.0 {
+        sim.step(step).unwrap();
+    }
+
+    sim.system().encounters().copied().collect()
+}
+
+#[test]
+fn distance_threshold_against_an_analytic_flyby() {
+    let encounters = flyby(EncounterRadius::Distance(0.1), 0.3);
+    assert_eq!(encounters.len(), 1);
+
+    // A third of the way into the step from 0.9 to 1.2
+    let encounter = encounters[0];
+    assert!((encounter.time - 1.0).abs() < 1e-12, "{}", encounter.time);
+    assert!((encounter.distance - 0.05).abs() < 1e-12);
+    assert!((encounter.speed - 1.0).abs() < 1e-12);
+
+    assert!(flyby(EncounterRadius::Distance(0.04), 0.3).is_empty());
+}
+
+#[test]
+fn approaches_on_a_step_boundary_are_logged_once() {
+    let encounters = flyby(EncounterRadius::Distance(0.1), 0.25);
+
+    assert_eq!(encounters.len(), 1, "{encounters:?}");
+    assert!((encounters[0].time - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn hill_threshold_scales_with_the_mutual_hill_radius() {
+    let run = |n: f64| {
+        // Crossing paths 1 AU from the sun, 0.005 AU apart at their closest, and fast enough for
+        // the sun to barely bend them on the way
+        let sun = BodyBuilder::new(1.0).build();
+        let planets = [(-1.0, 100.0), (1.0, -100.0)].map(|(x, v)| {
+            BodyBuilder::new(3e-6)
+                .with_position(DVec3::new(x, 1.0 + x * 0.0025, 0.0))
+                .with_velocity(DVec3::new(v, 0.0, 0.0))
+                .build()
+        });
+        let mut sim = SimBuilder::new(IntegratorType::Ias15 { epsilon: 1e-9 })
+            .with_bodies(vec![sun, planets[0], planets[1]])
+            .with_encounters(EncounterRadius::Hill(n))
+            .build();
+        for _ in 0..20 {
+            sim.step(0.001).unwrap();
+        }
+
+        let encounters = sim.system().encounters().copied().collect::<Vec<_>>();
+        (encounters, [planets[0].id(), planets[1].id()])
+    };
+
+    // Mutual Hill radius ∛((m₁ + m₂) / 3M) a ≈ 0.0126
+    let (encounters, planets) = run(1.0);
+    assert_eq!(encounters.len(), 1, "{encounters:?}");
+    assert_eq!(encounters[0].bodies, planets);
+    assert!((encounters[0].distance - 0.005).abs() < 1e-4);
+    assert!((encounters[0].time - 0.01).abs() < 1e-4);
+
+    let (encounters, _) = run(0.2);
+    assert!(encounters.is_empty(), "{encounters:?}");
+}
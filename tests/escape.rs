@@ -0,0 +1,108 @@
+use glam::DVec3;
+use planet_sim::sim::{
+    body::{Body, BodyBuilder},
+    escape::{Escape, EscapeCriterion},
+    event::Event,
+    integrator::IntegratorType,
+    orbit::Orbit,
+    units::Units,
+    Sim, SimBuilder,
+};
+
+fn run(criterion: EscapeCriterion, bodies: Vec<Body>, steps: usize) -> (Sim, Vec<Escape>) {
+    let mut sim = SimBuilder::new(IntegratorType::Ias15 { epsilon: 1e-9 })
+        .with_bodies(bodies)
+        .with_escapes(criterion)
+        .build();
+    for _ in 0..steps {
+        sim.step(0.1).unwrap();
+    }
+
+    let escapes = sim
+        .drain_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::Escape(escape) => Some(escape),
+            _ => None,
+        })
+        .collect();
+
+    (sim, escapes)
+}
+
+#[test]
+fn the_primary_never_escapes() {
+    let sun = BodyBuilder::new(1.0).build();
+    let far = BodyBuilder::new(1e-6)
+        .with_position(DVec3::new(200.0, 0.0, 0.0))
+        .build();
+    let (sim, escapes) = run(EscapeCriterion::Distance(100.0), vec![sun, far], 1);
+
+    assert_eq!(sim.system().len(), 1);
+    assert_eq!(sim.system().body(0).id(), sun.id());
+    assert_eq!(escapes.len(), 1);
+    assert_eq!(escapes[0].body, far.id());
+    assert!((escapes[0].position.x - 200.0).abs() < 1e-3);
+
+    // Unbound, but it's the comet leaving the sun
+    let sun = BodyBuilder::new(1.0).build();
+    let comet = BodyBuilder::new(1e-12)
+        .with_position(DVec3::new(10.0, 0.0, 0.0))
+        .with_velocity(DVec3::new(5.0, 5.0, 0.0))
+        .build();
+    let (sim, escapes) = run(EscapeCriterion::UnboundBeyond(5.0), vec![sun, comet], 1);
+
+    assert_eq!(sim.system().len(), 1);
+    assert_eq!(sim.system().body(0).id(), sun.id());
+    assert_eq!(escapes.len(), 1);
+    assert!(escapes[0].energy > 0.0);
+}
+
+#[test]
+fn bound_bodies_stay() {
+    let sun = BodyBuilder::new(1.0).build();
+    let planet = BodyBuilder::new(3e-6)
+        .with_orbit(
+            &sun,
+            Orbit::new(10.0, 0.9, 0.0, 0.0, 0.0, 0.0),
+            Units::Astronomical,
+        )
+        .build();
+    let (sim, escapes) = run(EscapeCriterion::UnboundBeyond(5.0), vec![sun, planet], 100);
+
+    assert_eq!(sim.system().len(), 2);
+    assert!(escapes.is_empty());
+}
+
+#[test]
+fn escapes_are_removed_mid_run() {
+    let sun = BodyBuilder::new(1.0).build();
+    let planet = BodyBuilder::new(3e-6)
+        .with_orbit(
+            &sun,
+            Orbit::new(1.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+            Units::Astronomical,
+        )
+        .build();
+    // Passing 20 AU after about two years
+    let comet = BodyBuilder::new(1e-12)
+        .with_position(DVec3::new(0.0, 2.0, 0.0))
+        .with_velocity(DVec3::new(0.0, 10.0, 0.0))
+        .build();
+    let (sim, escapes) = run(
+        EscapeCriterion::UnboundBeyond(20.0),
+        vec![sun, planet, comet],
+        30,
+    );
+
+    assert_eq!(escapes.len(), 1);
+    assert_eq!(escapes[0].body, comet.id());
+    assert!(escapes[0].position.length() > 20.0);
+    assert!((1.5..2.5).contains(&escapes[0].time), "{}", escapes[0].time);
+
+    // The rest carry on as before
+    let system = sim.system();
+    assert_eq!(system.len(), 2);
+    let distance = (system.body(1).position() - system.body(0).position()).length();
+    assert!((distance - 1.0).abs() < 1e-6, "{distance}");
+}
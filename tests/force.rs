@@ -0,0 +1,160 @@
+use glam::DVec3;
+use planet_sim::sim::{
+    body::BodyBuilder,
+    force::{Drag, RadiationPressure, Targets, Thrust, J2},
+    integrator::IntegratorType,
+    orbit::{Orbit, Primary},
+    units::Units,
+    Sim, SimBuilder,
+};
+use std::f64::consts::{PI, TAU};
+
+const INTEGRATORS: [IntegratorType; 8] = [
+    IntegratorType::Euler,
+    IntegratorType::SemiImplicitEuler,
+    IntegratorType::Leapfrog,
+    IntegratorType::Rk4,
+    IntegratorType::Yoshida,
+    IntegratorType::DormandPrince {
+        atol: 1e-10,
+        rtol: 1e-10,
+    },
+    IntegratorType::Ias15 { epsilon: 1e-9 },
+    IntegratorType::WisdomHolman,
+];
+
+#[test]
+fn thrust_accelerates_a_lone_body_under_every_integrator() {
+    let acceleration = DVec3::new(1.0, -2.0, 0.5);
+
+    for integrator in INTEGRATORS {
+        let mut sim = SimBuilder::new(integrator)
+            .with_bodies(vec![BodyBuilder::new(1.0).build()])
+            .with_force(Thrust { acceleration }, Targets::All)
+            .build();
+        for _ in 0..100 {
+            sim.step(0.01).unwrap();
+        }
+
+        let body = sim.system().body(0);
+        let expected = 0.5 * acceleration;
+        assert!(
+            (body.velocity() - acceleration).length() < 1e-12,
+            "{integrator:?}: velocity {}",
+            body.velocity()
+        );
+        // The Euler methods are off by half a step's worth of velocity either way
+        assert!(
+            (body.position() - expected).length() < 0.6 * 0.01 * acceleration.length(),
+            "{integrator:?}: position {}",
+            body.position()
+        );
+    }
+}
+
+#[test]
+fn radiation_pressure_can_balance_gravity() {
+    let sun = BodyBuilder::new(1.0).build();
+    let grain = BodyBuilder::test_particle()
+        .with_position(DVec3::new(1.0, 0.0, 0.0))
+        .build();
+    let mut sim = SimBuilder::new(IntegratorType::Rk4)
+        .with_bodies(vec![sun, grain])
+        .with_force(
+            RadiationPressure {
+                source: sun.id(),
+                beta: 1.0,
+            },
+            Targets::Bodies(vec![grain.id()]),
+        )
+        .build();
+    for _ in 0..100 {
+        sim.step(0.01).unwrap();
+    }
+
+    let grain = sim.system().body(1);
+    assert!(grain.velocity().length() < 1e-12, "{}", grain.velocity());
+    assert!((grain.position() - DVec3::X).length() < 1e-12);
+}
+
+#[test]
+fn drag_damps_velocity_relative_to_its_frame() {
+    let timescale = 0.5;
+    let gas = BodyBuilder::test_particle()
+        .with_velocity(DVec3::new(0.0, 1.0, 0.0))
+        .build();
+    let grain = BodyBuilder::test_particle()
+        .with_position(DVec3::new(1.0, 0.0, 0.0))
+        .with_velocity(DVec3::new(2.0, 1.0, 0.0))
+        .build();
+    let mut sim = SimBuilder::new(IntegratorType::Rk4)
+        .with_bodies(vec![gas, grain])
+        .with_force(
+            Drag {
+                frame: gas.id(),
+                timescale,
+            },
+            Targets::All,
+        )
+        .build();
+    for _ in 0..100 {
+        sim.step(0.01).unwrap();
+    }
+
+    let relative = sim.system().body(1).velocity() - sim.system().body(0).velocity();
+    let expected = DVec3::new(2.0 * (-1.0 / timescale).exp(), 0.0, 0.0);
+    assert!((relative - expected).length() < 1e-9, "{relative}");
+}
+
+#[test]
+fn j2_regresses_the_node_and_conserves_momentum() {
+    let (j2, radius, a, inclination) = (0.1, 0.3, 1.0, PI / 4.0);
+    let planet = BodyBuilder::new(1.0).build();
+    let moon = BodyBuilder::new(1e-3)
+        .with_orbit(
+            &planet,
+            Orbit::new(a, 0.0, inclination, 0.0, 0.0, 0.0),
+            Units::Astronomical,
+        )
+        .build();
+    let mut sim = SimBuilder::new(IntegratorType::Ias15 { epsilon: 1e-9 })
+        .with_bodies(vec![planet, moon])
+        .with_force(
+            J2 {
+                primary: planet.id(),
+                j2,
+                radius,
+            },
+            Targets::All,
+        )
+        .build();
+    let momentum = |sim: &Sim| {
+        sim.system()
+            .bodies()
+            .map(|body| body.mass() * body.velocity())
+            .sum::<DVec3>()
+    };
+    let initial = momentum(&sim);
+
+    let duration = 10.0;
+    for _ in 0..100 {
+        sim.step(duration / 100.0).unwrap();
+    }
+
+    // dΩ/dt = -3/2 n J2 (R/a)² cos i, for a circular orbit
+    let n = TAU * 1.001_f64.sqrt();
+    let rate = -1.5 * n * j2 * (radius / a).powi(2) * inclination.cos();
+    let node = sim
+        .system()
+        .elements(moon.id(), Primary::Body(planet.id()))
+        .unwrap()
+        .node;
+    // Just short of a full turn, having started at zero
+    let regressed = node - TAU;
+    assert!(
+        (regressed - rate * duration).abs() < 0.05 * (rate * duration).abs(),
+        "node {regressed}, expected {}",
+        rate * duration
+    );
+    assert!((momentum(&sim) - initial).length() < 1e-15);
+}
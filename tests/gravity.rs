@@ -0,0 +1,164 @@
+use glam::DVec3;
+use planet_sim::sim::gravity::{fmm, GravityType};
+
+// xorshift64*, so the samples are the same on every run
+struct Random(u64);
+
+impl Random {
+    // Uniform on [0, 1)
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// `n` equal masses drawn from a Plummer sphere of unit scale radius and mass
+fn plummer(n: usize, seed: u64) -> (Vec<DVec3>, Vec<f64>) {
+    let mut random = Random(seed);
+
+    let positions = (0..n)
+        .map(|_| {
+            // Inverting the cumulative mass profile M(r) = r³ / (1 + r²)^(3/2)
+            let radius = 1.0 / (random.next().powf(-2.0 / 3.0) - 1.0).sqrt();
+            let z = 2.0 * random.next() - 1.0;
+            let phi = std::f64::consts::TAU * random.next();
+            let s = (1.0 - z * z).sqrt();
+            radius * DVec3::new(s * phi.cos(), s * phi.sin(), z)
+        })
+        .collect();
+
+    (positions, vec![1.0 / n as f64; n])
+}
+
+// Error of every body's acceleration relative to direct summation, sorted
+fn relative_errors(gravity: GravityType, positions: &[DVec3], masses: &[f64]) -> Vec<f64> {
+    let exact = GravityType::Direct
+        .gravity()
+        .accelerations(positions, masses, 1.0, 0.0);
+    let approximate = gravity.gravity().accelerations(positions, masses, 1.0, 0.0);
+
+    let mut errors = exact
+        .iter()
+        .zip(&approximate)
+        .map(|(exact, approximate)| (*approximate - *exact).length() / exact.length())
+        .collect::<Vec<_>>();
+    errors.sort_by(f64::total_cmp);
+    errors
+}
+
+#[test]
+fn barnes_hut_against_direct_summation() {
+    let (positions, masses) = plummer(2000, 1);
+
+    for (theta, median, max) in [(0.3, 2e-3, 0.03), (0.5, 1e-2, 0.15), (0.8, 3e-2, 0.5)] {
+        let errors = relative_errors(GravityType::BarnesHut { theta }, &positions, &masses);
+        let (actual_median, actual_max) = (errors[errors.len() / 2], errors[errors.len() - 1]);
+
+        assert!(
+            actual_median < median,
+            "θ = {theta}: median error {actual_median}"
+        );
+        assert!(actual_max < max, "θ = {theta}: max error {actual_max}");
+    }
+}
+
+#[test]
+fn fmm_converges_with_order() {
+    let (positions, masses) = plummer(2000, 2);
+
+    let errors = (1..=8)
+        .map(|order| relative_errors(GravityType::Fmm { order }, &positions, &masses))
+        .collect::<Vec<_>>();
+    let medians = errors
+        .iter()
+        .map(|errors| errors[errors.len() / 2])
+        .collect::<Vec<_>>();
+
+    for (order, pair) in medians.windows(2).enumerate() {
+        assert!(pair[1] < pair[0], "order {}: {medians:?}", order + 2);
+    }
+    assert!(medians[0] < 0.1, "{medians:?}");
+    assert!(medians[7] < 1e-5, "{medians:?}");
+
+    let worst = errors[7][errors[7].len() - 1];
+    assert!(worst < 1e-2, "order 8: max error {worst}");
+}
+
+#[test]
+#[should_panic(expected = "order of at least 1")]
+fn fmm_rejects_order_zero() {
+    GravityType::Fmm { order: 0 }.gravity();
+}
+
+#[test]
+#[should_panic(expected = "up to order 16")]
+fn fmm_rejects_orders_past_the_cap() {
+    GravityType::Fmm {
+        order: fmm::MAX_ORDER + 1,
+    }
+    .gravity();
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn thread_count_does_not_change_results() {
+    let (positions, masses) = plummer(2000, 3);
+
+    for gravity in [
+        GravityType::Direct,
+        GravityType::BarnesHut { theta: 0.5 },
+        GravityType::Fmm { order: 4 },
+    ] {
+        let accelerations = |threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| {
+                    gravity
+                        .gravity()
+                        .accelerations(&positions, &masses, 1.0, 0.01)
+                        .iter()
+                        .map(|a| a.to_array().map(f64::to_bits))
+                        .collect::<Vec<_>>()
+                })
+        };
+
+        assert!(accelerations(1) == accelerations(4), "{gravity:?}");
+    }
+}
+
+#[test]
+fn softening_keeps_coincident_bodies_finite() {
+    // Pairs of bodies on top of each other
+    let (mut positions, mut masses) = plummer(500, 4);
+    positions.extend_from_within(..250);
+    masses.extend_from_within(..250);
+
+    for gravity in [
+        GravityType::Direct,
+        GravityType::BarnesHut { theta: 0.5 },
+        GravityType::Fmm { order: 4 },
+    ] {
+        let accelerations = gravity
+            .gravity()
+            .accelerations(&positions, &masses, 1.0, 0.01);
+        assert!(accelerations.iter().all(|a| a.is_finite()), "{gravity:?}");
+    }
+}
+
+#[test]
+fn softening_follows_the_plummer_kernel() {
+    let (d, softening) = (0.3, 0.4);
+    let positions = [DVec3::ZERO, DVec3::new(d, 0.0, 0.0)];
+    let accelerations =
+        GravityType::Direct
+            .gravity()
+            .accelerations(&positions, &[2.0, 1.0], 1.0, softening);
+
+    // a = m d / (d² + ε²)^(3/2), with d² + ε² = 1/4
+    assert!((accelerations[0].x - 8.0 * d).abs() < 1e-15);
+    assert!((accelerations[1].x + 16.0 * d).abs() < 1e-15);
+}
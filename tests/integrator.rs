@@ -0,0 +1,180 @@
+use glam::DVec3;
+use planet_sim::sim::{
+    body::{Body, BodyBuilder},
+    cr3bp::Cr3bp,
+    frame::Frame,
+    integrator::IntegratorType,
+    kepler,
+    orbit::Orbit,
+    units::Units,
+    Sim, SimBuilder,
+};
+
+// A test particle on `orbit` about a unit mass, whose period is a year
+fn kepler_orbit(integrator: IntegratorType, orbit: Orbit) -> (Sim, Body) {
+    let sun = BodyBuilder::new(1.0).build();
+    let planet = BodyBuilder::test_particle()
+        .with_orbit(&sun, orbit, Units::Astronomical)
+        .build();
+    let sim = SimBuilder::new(integrator)
+        .with_bodies(vec![sun, planet])
+        .build();
+
+    (sim, planet)
+}
+
+// Distance from where the particle should be after `steps` steps covering `duration`
+fn position_error(integrator: IntegratorType, orbit: Orbit, duration: f64, steps: usize) -> f64 {
+    let (mut sim, planet) = kepler_orbit(integrator, orbit);
+    for _ in 0..steps {
+        sim.step(duration / steps as f64).unwrap();
+    }
+
+    let mu = Units::Astronomical.gravitational_constant();
+    let (expected, _) = kepler::propagate(planet.position(), planet.velocity(), mu, duration);
+    let positions = sim.system().positions();
+    (positions[1] - positions[0] - expected).length()
+}
+
+#[test]
+fn rk4_converges_at_fourth_order() {
+    let orbit = Orbit::new(1.0, 0.5, 0.3, 0.0, 1.0, 0.0);
+    let errors =
+        [200, 400, 800].map(|steps| position_error(IntegratorType::Rk4, orbit, 1.0, steps));
+
+    for pair in errors.windows(2) {
+        let order = (pair[0] / pair[1]).log2();
+        assert!((3.8..4.4).contains(&order), "order {order}");
+    }
+}
+
+#[test]
+fn dormand_prince_follows_an_eccentric_orbit() {
+    let integrator = IntegratorType::DormandPrince {
+        atol: 1e-12,
+        rtol: 1e-12,
+    };
+    let orbit = Orbit::new(1.0, 0.99, 0.3, 0.0, 1.0, 3.0);
+    let (mut sim, planet) = kepler_orbit(integrator, orbit);
+    // Through periapsis, where the speed is ~14 times the mean
+    for _ in 0..10 {
+        sim.step(0.1).unwrap();
+    }
+
+    let mu = Units::Astronomical.gravitational_constant();
+    let (expected, _) = kepler::propagate(planet.position(), planet.velocity(), mu, 1.0);
+    let positions = sim.system().positions();
+    let error = (positions[1] - positions[0] - expected).length();
+
+    // Shrinking onto periapsis takes rejected steps, and most steps are spent there
+    let stats = sim.system().integrator().stats().unwrap();
+    assert!(error < 1e-9, "error {error}");
+    assert!(stats.accepted > 100);
+    assert!(stats.rejected > 0);
+}
+
+#[test]
+fn dormand_prince_takes_a_purely_relative_tolerance() {
+    let integrator = IntegratorType::DormandPrince {
+        atol: 0.0,
+        rtol: 1e-10,
+    };
+    // Planar, so every z stays at zero
+    let orbit = Orbit::new(1.0, 0.5, 0.0, 0.0, 1.0, 0.0);
+    let error = position_error(integrator, orbit, 0.5, 1);
+
+    assert!(error < 1e-8, "error {error}");
+}
+
+#[test]
+fn dormand_prince_gives_up_on_singular_forces() {
+    let integrator = IntegratorType::DormandPrince {
+        atol: 1e-10,
+        rtol: 1e-10,
+    };
+    let bodies = vec![BodyBuilder::new(1.0).build(), BodyBuilder::new(1.0).build()];
+    let mut sim = SimBuilder::new(integrator).with_bodies(bodies).build();
+
+    // Coincident and unsoftened, so every force is NaN
+    sim.step(0.1).unwrap();
+
+    let stats = sim.system().integrator().stats().unwrap();
+    assert!(sim.system().positions()[0].is_nan());
+    assert_eq!(stats.accepted, 1);
+    assert!(stats.rejected > 0);
+}
+
+#[test]
+fn ias15_conserves_energy_over_ten_thousand_orbits() {
+    let sun = BodyBuilder::new(1.0).build();
+    let planet = BodyBuilder::new(1e-3)
+        .with_orbit(
+            &sun,
+            Orbit::new(1.0, 0.5, 0.0, 0.0, 0.0, 0.0),
+            Units::Astronomical,
+        )
+        .build();
+    // At rest, so round-off in the positions doesn't grow as the system drifts away
+    let mut sim = SimBuilder::new(IntegratorType::Ias15 { epsilon: 1e-9 })
+        .with_bodies(vec![sun, planet])
+        .with_frame(Frame::Barycentric)
+        .build();
+
+    let period = 1.0 / 1.001_f64.sqrt();
+    for _ in 0..10_000 {
+        sim.step(period).unwrap();
+    }
+
+    let drift = sim.system().drift().energy;
+    assert!(drift < 2e-14, "energy drift {drift}");
+}
+
+#[test]
+fn ias15_steps_through_an_equilibrium() {
+    // At L4, where every acceleration cancels to round-off
+    let problem = Cr3bp::new(0.01);
+    let l4 = problem.lagrange_points()[3];
+    let particle = BodyBuilder::test_particle().with_position(l4).build();
+    let mut sim = SimBuilder::new(IntegratorType::Ias15 { epsilon: 1e-9 })
+        .with_bodies(vec![particle])
+        .with_restricted(problem)
+        .build();
+    for _ in 0..10 {
+        sim.step(0.1).unwrap();
+    }
+
+    // Rather than shrinking forever against the noise
+    let stats = sim.system().integrator().stats().unwrap();
+    assert!(stats.accepted < 100, "{stats:?}");
+    assert!((sim.system().positions()[0] - l4).length() < 1e-14);
+    assert!(sim.system().velocities()[0].length() < 1e-14);
+}
+
+#[test]
+fn wisdom_holman_moves_test_particles_alone() {
+    let velocities = [DVec3::new(1.0, 0.0, 0.0), DVec3::new(0.0, -2.0, 0.5)];
+    let bodies = velocities
+        .iter()
+        .map(|v| BodyBuilder::test_particle().with_velocity(*v).build())
+        .collect();
+    let mut sim = SimBuilder::new(IntegratorType::WisdomHolman)
+        .with_bodies(bodies)
+        .build();
+    for _ in 0..10 {
+        sim.step(0.1).unwrap();
+    }
+
+    // Without mass there's no gravity, so in straight lines
+    for (position, velocity) in sim.system().positions().iter().zip(velocities) {
+        assert!((*position - velocity).length() < 1e-15, "{position}");
+    }
+}
+
+#[test]
+fn wisdom_holman_follows_an_escaping_comet() {
+    // Two-body motion is all Kepler drift, so exact however long the steps. A century out the
+    // comet is some 450 AU away
+    let orbit = Orbit::new(-2.0, 1.4, 0.5, 1.0, 2.0, 0.3);
+    let error = position_error(IntegratorType::WisdomHolman, orbit, 100.0, 100);
+    assert!(error < 1e-6, "error {error}");
+}
@@ -0,0 +1,86 @@
+use glam::DVec3;
+use planet_sim::sim::{
+    body::BodyBuilder, integrator::IntegratorType, kepler, orbit::Orbit, units::Units, SimBuilder,
+};
+
+#[test]
+fn propagate_matches_mean_motion() {
+    let mu = Units::Astronomical.gravitational_constant();
+
+    for (a, e) in [
+        (1.0, 0.2),
+        (0.3, 0.95),
+        (-2.0, 1.4),
+        (-0.5, 10.0),
+        (0.5, 1.0),
+    ] {
+        let orbit = Orbit::new(a, e, 0.5, 1.0, 2.0, 0.3);
+        // Parabolic orbits take their periapsis distance in place of the semi-major axis
+        let motion = if e == 1.0 {
+            (mu / (2.0 * a * a * a)).sqrt()
+        } else {
+            (mu / f64::abs(a * a * a)).sqrt()
+        };
+
+        for dt in [0.01, 0.37, -1.3, 10.0, 30.0, -100.0, 1000.0] {
+            let (position, velocity) = orbit.state(mu);
+            let expected = Orbit::new(a, e, 0.5, 1.0, 2.0, 0.3 + motion * dt).state(mu);
+            let actual = kepler::propagate(position, velocity, mu, dt);
+
+            assert!(
+                (actual.0 - expected.0).length() < 1e-9 * expected.0.length(),
+                "a = {a}, e = {e}, dt = {dt}: {} against {}",
+                actual.0,
+                expected.0
+            );
+            assert!((actual.1 - expected.1).length() < 1e-9 * expected.1.length());
+        }
+    }
+}
+
+#[test]
+fn propagate_far_along_a_hyperbola() {
+    let (position, velocity, mu) = (DVec3::X, DVec3::new(0.0, 3.0, 0.0), 1.0);
+    let energy = |(r, v): (DVec3, DVec3)| v.length_squared() / 2.0 - mu / r.length();
+
+    for dt in [10.0, 1e3, 1e6, -1e6] {
+        let (r, v) = kepler::propagate(position, velocity, mu, dt);
+
+        // Out at the asymptotic speed, still on the same orbit
+        assert!(r.is_finite() && v.is_finite(), "dt = {dt}: {r}, {v}");
+        assert!(
+            ((energy((r, v)) - energy((position, velocity))) / 3.5).abs() < 1e-12,
+            "dt = {dt}"
+        );
+        assert!((r.cross(v) - position.cross(velocity)).length() < 1e-9 * 3.0);
+
+        // And back again, to round-off in how far out it got
+        let (back, _) = kepler::propagate(r, v, mu, -dt);
+        assert!(
+            (back - position).length() < 1e-9 * r.length(),
+            "dt = {dt}: {back}"
+        );
+    }
+}
+
+#[test]
+fn integrator_against_propagate() {
+    let sun = BodyBuilder::new(1.0).build();
+    let planet = BodyBuilder::new(0.0)
+        .with_position(DVec3::new(1.0, 0.0, 0.0))
+        .with_velocity(DVec3::new(0.0, 7.0, 0.5))
+        .build();
+    let mut sim = SimBuilder::new(IntegratorType::Ias15 { epsilon: 1e-9 })
+        .with_bodies(vec![sun, planet])
+        .build();
+
+    for _ in 0..100 {
+        sim.step(0.01).unwrap();
+    }
+
+    let mu = Units::Astronomical.gravitational_constant();
+    let (position, _) = kepler::propagate(planet.position(), planet.velocity(), mu, 1.0);
+    let system = sim.system();
+    let actual = system.positions()[1] - system.positions()[0];
+    assert!((actual - position).length() < 1e-10);
+}
@@ -0,0 +1,89 @@
+use planet_sim::sim::{
+    body::BodyBuilder,
+    integrator::IntegratorType,
+    orbit::{Orbit, Primary},
+    units::Units,
+    SimBuilder,
+};
+use std::f64::consts::{PI, TAU};
+
+fn close_angle(a: f64, b: f64) -> bool {
+    let difference = (a - b).rem_euclid(TAU);
+    difference.min(TAU - difference) < 1e-9
+}
+
+#[test]
+fn elements_round_trip() {
+    let orbits = [
+        Orbit::new(1.0, 0.3, 0.4, 1.0, 2.0, 0.7),
+        Orbit::new(5.2, 0.05, 0.02, 4.0, 0.5, -2.5),
+        Orbit::new(0.4, 0.9, 2.8, 0.3, 5.0, 3.0),
+        Orbit::new(-2.0, 1.5, 1.2, 2.0, 1.0, 1.5),
+        Orbit::new(0.5, 1.0, 0.7, 5.5, 3.5, -0.8),
+        Orbit::new(2.0, 0.2, 0.3, 1.0, 2.0, 0.0).with_mean_longitude(4.0),
+        Orbit::new(3.0, 0.6, 0.1, 1.0, 2.0, 0.0).with_true_anomaly(-1.2),
+    ];
+
+    let sun = BodyBuilder::new(1.0).build();
+    let planets = orbits.map(|orbit| {
+        BodyBuilder::new(1e-3)
+            .with_orbit(&sun, orbit, Units::Astronomical)
+            .build()
+    });
+    let sim = SimBuilder::new(IntegratorType::Rk4)
+        .with_bodies([&[sun][..], &planets].concat())
+        .build();
+
+    for (orbit, planet) in orbits.iter().zip(&planets) {
+        let elements = sim
+            .system()
+            .elements(planet.id(), Primary::Body(sun.id()))
+            .unwrap();
+
+        let a = orbit.semi_major_axis;
+        assert!((elements.semi_major_axis - a).abs() < 1e-9 * a.abs());
+        assert!((elements.eccentricity - orbit.eccentricity).abs() < 1e-9);
+        assert!((elements.inclination - orbit.inclination).abs() < 1e-9);
+        assert!(close_angle(elements.node, orbit.node));
+        assert!(close_angle(elements.periapsis, orbit.periapsis));
+        assert!(close_angle(elements.true_anomaly, orbit.true_anomaly()));
+        assert_eq!(elements.period.is_some(), orbit.eccentricity < 1.0);
+
+        // And back to the same state
+        let mu = Units::Astronomical.gravitational_constant() * (sun.mass() + planet.mass());
+        let (position, velocity) = elements.orbit().state(mu);
+        assert!((position - planet.position()).length() < 1e-9 * position.length());
+        assert!((velocity - planet.velocity()).length() < 1e-9 * velocity.length());
+    }
+}
+
+#[test]
+fn circular_equatorial_elements() {
+    let sun = BodyBuilder::new(1.0).build();
+    let planet = BodyBuilder::new(0.0)
+        .with_orbit(
+            &sun,
+            Orbit::new(1.0, 0.0, 0.0, 0.0, 0.0, PI / 3.0),
+            Units::Astronomical,
+        )
+        .build();
+
+    let elements = planet.elements(&sun, Units::Astronomical);
+    assert!(elements.eccentricity < 1e-12);
+    assert_eq!((elements.node, elements.periapsis), (0.0, 0.0));
+    assert!(close_angle(elements.true_anomaly, PI / 3.0));
+    assert!((elements.period.unwrap() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn interior_primary() {
+    let sim = SimBuilder::new(IntegratorType::Rk4).build();
+    let earth = sim.system().bodies().last().unwrap();
+    let elements = sim
+        .system()
+        .elements(earth.id(), Primary::Interior)
+        .unwrap();
+
+    assert!((elements.semi_major_axis - 1.000_002_61).abs() < 1e-9);
+    assert!((elements.eccentricity - 0.016_711_23).abs() < 1e-9);
+}
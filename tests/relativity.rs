@@ -0,0 +1,61 @@
+use planet_sim::sim::{
+    body::BodyBuilder,
+    integrator::IntegratorType,
+    orbit::{Orbit, Primary},
+    units::Units,
+    Sim, SimBuilder,
+};
+use std::f64::consts::{PI, TAU};
+
+fn mercury(relativity: bool) -> (Sim, usize) {
+    let sun = BodyBuilder::new(1.0).build();
+    let mercury = BodyBuilder::new(1.66e-7)
+        .with_orbit(
+            &sun,
+            Orbit::new(0.387_098, 0.205_630, 0.0, 0.0, 0.0, 0.0),
+            Units::Astronomical,
+        )
+        .build();
+
+    let mut builder =
+        SimBuilder::new(IntegratorType::Ias15 { epsilon: 1e-9 }).with_bodies(vec![sun, mercury]);
+    if relativity {
+        builder = builder.with_relativity();
+    }
+
+    (builder.build(), mercury.id())
+}
+
+#[test]
+fn mercury_perihelion_precession() {
+    let (mut newtonian, newtonian_id) = mercury(false);
+    let (mut relativistic, relativistic_id) = mercury(true);
+
+    // Ten orbits, ending back at perihelion
+    let period = newtonian
+        .system()
+        .elements(newtonian_id, Primary::Interior)
+        .unwrap()
+        .period
+        .unwrap();
+    let steps = 1000;
+    for _ in 0..steps {
+        newtonian.step(10.0 * period / steps as f64).unwrap();
+        relativistic.step(10.0 * period / steps as f64).unwrap();
+    }
+
+    let periapsis = |sim: &Sim, id| {
+        sim.system()
+            .elements(id, Primary::Interior)
+            .unwrap()
+            .periapsis
+    };
+    let precession =
+        (periapsis(&relativistic, relativistic_id) - periapsis(&newtonian, newtonian_id) + PI)
+            .rem_euclid(TAU)
+            - PI;
+
+    // Arcseconds per century, against the 42.98″ observed
+    let rate = precession / (10.0 * period) * 100.0 * 180.0 / PI * 3600.0;
+    assert!((rate - 42.98).abs() < 0.5, "{rate}″ per century");
+}
@@ -0,0 +1,96 @@
+use glam::DVec3;
+use planet_sim::sim::{
+    body::BodyBuilder,
+    cr3bp::Cr3bp,
+    force::{Drag, Targets},
+    integrator::IntegratorType,
+    orbit::Orbit,
+    units::Units,
+    Sim, SimBuilder,
+};
+
+// Two heavy planets close enough to be chaotic, and the id of their sun
+fn planets(integrator: IntegratorType) -> (SimBuilder, usize) {
+    let sun = BodyBuilder::new(1.0).build();
+    let planets = [(1.0, 0.1, 0.0), (1.4, 0.2, 2.0)].map(|(a, e, mean)| {
+        BodyBuilder::new(1e-3)
+            .with_orbit(
+                &sun,
+                Orbit::new(a, e, 0.05, 0.0, 1.0, mean),
+                Units::Astronomical,
+            )
+            .build()
+    });
+    let builder = SimBuilder::new(integrator).with_bodies(vec![sun, planets[0], planets[1]]);
+    (builder, sun.id())
+}
+
+// Largest distance from the start in position and velocity after 1000 steps forward and as many
+// back
+fn round_trip(mut sim: Sim) -> (f64, f64) {
+    let positions = sim.system().positions().to_vec();
+    let velocities = sim.system().velocities().to_vec();
+
+    for _ in 0..1000 {
+        sim.step(0.01).unwrap();
+    }
+    for _ in 0..1000 {
+        sim.step(-0.01).unwrap();
+    }
+    assert!(sim.system().time().abs() < 1e-12);
+
+    let error = |initial: &[DVec3], current: &[DVec3]| {
+        initial
+            .iter()
+            .zip(current)
+            .map(|(a, b)| (*a - *b).length())
+            .fold(0.0, f64::max)
+    };
+    (
+        error(&positions, sim.system().positions()),
+        error(&velocities, sim.system().velocities()),
+    )
+}
+
+#[test]
+fn forward_then_backward() {
+    for integrator in [
+        IntegratorType::Leapfrog,
+        IntegratorType::Yoshida,
+        IntegratorType::WisdomHolman,
+    ] {
+        assert!(integrator.is_time_reversible());
+
+        let sim = planets(integrator).0.build();
+        assert!(sim.system().is_time_reversible());
+
+        let (position, velocity) = round_trip(sim);
+        assert!(position < 1e-12, "{integrator:?}: {position}");
+        assert!(velocity < 1e-11, "{integrator:?}: {velocity}");
+    }
+}
+
+#[test]
+fn velocity_dependent_forces_are_not_reversible() {
+    let (builder, sun) = planets(IntegratorType::Leapfrog);
+    let drag = Drag {
+        frame: sun,
+        timescale: 10.0,
+    };
+    let sim = builder.with_force(drag, Targets::All).build();
+    assert!(!sim.system().is_time_reversible());
+
+    // Leapfrog no longer retraces its path
+    let (position, _) = round_trip(sim);
+    assert!(position > 1e-6, "{position}");
+
+    let sim = planets(IntegratorType::Yoshida).0.with_relativity().build();
+    assert!(!sim.system().is_time_reversible());
+
+    let particle = BodyBuilder::test_particle().with_position(DVec3::X).build();
+    let sim = SimBuilder::new(IntegratorType::Leapfrog)
+        .with_bodies(vec![particle])
+        .with_restricted(Cr3bp::new(0.01))
+        .build();
+    assert!(!sim.system().is_time_reversible());
+}
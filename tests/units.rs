@@ -0,0 +1,63 @@
+use glam::DVec3;
+use planet_sim::sim::units::Units;
+
+const UNITS: [Units; 4] = [
+    Units::Astronomical,
+    Units::Si,
+    Units::Kilometers,
+    Units::NBody {
+        length: 3.086e16,
+        mass: 1.989e30,
+    },
+];
+
+fn close(a: f64, b: f64, tolerance: f64) -> bool {
+    (a - b).abs() <= tolerance * b.abs()
+}
+
+#[test]
+fn astronomical_units_against_known_values() {
+    let au = Units::Astronomical;
+
+    let speed = au.convert_velocity(DVec3::X, Units::Kilometers).x;
+    assert!(close(speed, 4.7404, 1e-4), "1 AU/yr = {speed} km/s");
+
+    let days = au.convert_time(1.0, Units::Si) / 86_400.0;
+    assert!(close(days, 365.2569, 1e-6), "1 yr = {days} days");
+
+    let c = au.speed_of_light();
+    assert!(close(c, 63_240.0, 1e-4), "c = {c} AU/yr");
+}
+
+#[test]
+fn conversions_round_trip() {
+    for from in UNITS {
+        for to in UNITS {
+            let there_and_back = |convert: fn(&Units, f64, Units) -> f64| {
+                convert(&to, convert(&from, 1.234, to), from)
+            };
+            assert!(close(there_and_back(Units::convert_length), 1.234, 1e-15));
+            assert!(close(there_and_back(Units::convert_mass), 1.234, 1e-15));
+            assert!(close(there_and_back(Units::convert_time), 1.234, 1e-15));
+
+            let v = DVec3::new(1.0, -2.0, 3.0);
+            let back = to.convert_velocity(from.convert_velocity(v, to), from);
+            assert!(
+                (back - v).length() < 1e-14 * v.length(),
+                "{from:?} to {to:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn every_system_agrees_on_g() {
+    let si = Units::Si.gravitational_constant();
+
+    for units in UNITS {
+        // G L³ / (M T²) in SI
+        let g = units.gravitational_constant() * units.length().powi(3)
+            / (units.mass() * units.time().powi(2));
+        assert!(close(g, si, 1e-12), "{units:?}: {g}");
+    }
+}